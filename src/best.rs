@@ -111,7 +111,7 @@ impl<T> Eq for CountOf<T> {}
 
 impl<T> PartialOrd for CountOf<T> {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.count.partial_cmp(&other.count)
+        Some(self.cmp(other))
     }
 }
 
@@ -0,0 +1,443 @@
+use itertools::Itertools;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::One;
+use num_traits::Zero;
+
+use crate::crt;
+use crate::factors;
+use crate::huge::factorial_without_prime;
+use crate::mod_inverse;
+use crate::mul_mod;
+use crate::pow_mod;
+
+/// The binomial coefficient C(n, k), the number of ways of choosing k
+/// things from n, which is 0 when k > n.
+///
+/// ```
+/// // Problem 15: routes through a 20 × 20 grid.
+/// assert_eq!(euler::binomial(40, 20), 137846528820);
+/// assert_eq!(euler::binomial(3, 5), 0);
+/// ```
+///
+/// Panics if the result doesn't fit in a u128. Use `binomial_big` for
+/// those.
+pub fn binomial(n: u64, k: u64) -> u128 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    // After step i, c = C(n, i + 1). Dividing out the gcd first keeps
+    // the intermediate products down to the size of the result.
+    (0..k).fold(1_u128, |c, i| {
+        let (top, bottom) = (u128::from(n - i), u128::from(i + 1));
+        let g = c.gcd(&bottom);
+        (c / g)
+            .checked_mul(top / (bottom / g))
+            .expect("Binomial coefficient is too big for a u128")
+    })
+}
+
+/// The binomial coefficient C(n, k) as a BigInt.
+///
+/// ```
+/// assert_eq!(euler::binomial_big(100, 50).to_string(), "100891344545564193334812497256");
+/// ```
+pub fn binomial_big(n: u64, k: u64) -> BigInt {
+    if k > n {
+        return BigInt::zero();
+    }
+    let k = k.min(n - k);
+    (0..k).fold(BigInt::one(), |c, i| c * (n - i) / (i + 1))
+}
+
+/// C(n, k) mod the prime p, by Lucas's theorem: writing n and k in base
+/// p, it's the product of the binomials of their digits. That takes
+/// O(min(k, p) log n) steps, so p can be big as long as k is small, or
+/// the other way round.
+///
+/// ```
+/// assert_eq!(euler::lucas_binomial(1_000_000_000_000, 500_000_000_000, 7), 0);
+/// assert_eq!(euler::lucas_binomial(10, 3, 1_000_000_007), 120);
+/// ```
+///
+/// Gives nonsense if p isn't prime.
+pub fn lucas_binomial(mut n: u64, mut k: u64, p: u64) -> u64 {
+    let mut result = 1 % p;
+    while k > 0 {
+        let (ni, ki) = (n % p, k % p);
+        if ki > ni {
+            return 0;
+        }
+        result = mul_mod(result, small_binomial_mod(ni, ki, p), p);
+        n /= p;
+        k /= p;
+    }
+    result
+}
+
+// C(n, k) mod the prime p, for k ≤ n < p.
+fn small_binomial_mod(n: u64, k: u64, p: u64) -> u64 {
+    let k = k.min(n - k);
+    let (top, bottom) = (0..k).fold((1 % p, 1 % p), |(top, bottom), i| {
+        (mul_mod(top, n - i, p), mul_mod(bottom, i + 1, p))
+    });
+    mul_mod(top, mod_inverse(bottom, p).unwrap(), p)
+}
+
+/// C(n, k) mod p^e, for a prime p.
+///
+/// This follows Granville's generalisation of Lucas's theorem. C(n, k)
+/// is n! / k! (n - k)!, and each factorial splits into a power of p
+/// times a part coprime to p, which can be worked out mod p^e from a
+/// table of one period. Kummer's theorem says the powers of p come
+/// out as a count of carries, but here they just fall out of the
+/// factorials. It takes O(p^e + log n) steps, and a table of p^e
+/// numbers, which is why p^e can't be more than 10^7.
+///
+/// ```
+/// // C(10, 5) = 252 = 4 × 63
+/// assert_eq!(euler::binomial_mod_prime_power(10, 5, 2, 3), 4);
+/// assert_eq!(euler::binomial_mod_prime_power(10, 5, 3, 2), 0);
+/// ```
+///
+/// Panics if p^e is more than 10^7, and gives nonsense if p isn't prime.
+pub fn binomial_mod_prime_power(n: u64, k: u64, p: u64, e: u32) -> u64 {
+    let q = p.checked_pow(e).expect("Prime power is too big");
+    if k > n {
+        return 0;
+    }
+    let (top, e_top) = factorial_without_prime(n, p, q);
+    let (left, e_left) = factorial_without_prime(k, p, q);
+    let (right, e_right) = factorial_without_prime(n - k, p, q);
+    let exponent = e_top - e_left - e_right;
+    if exponent >= u64::from(e) {
+        return 0;
+    }
+    let bottom = mul_mod(left, right, q);
+    let unit = mul_mod(top, mod_inverse(bottom, q).unwrap(), q);
+    mul_mod(unit, p.pow(exponent as u32), q)
+}
+
+/// C(n, k) mod any m, worked out mod each prime power in m and put back
+/// together with the Chinese remainder theorem.
+///
+/// ```
+/// // The last 6 digits of C(1000, 500).
+/// assert_eq!(euler::binomial_mod(1000, 500, 1_000_000), 216320);
+/// assert_eq!(euler::binomial_mod(40, 20, 1), 0);
+/// ```
+///
+/// Panics if m is 0, or if a prime appears more than once in m and its
+/// power is more than 10^7, as for `binomial_mod_prime_power`.
+pub fn binomial_mod(n: u64, k: u64, m: u64) -> u64 {
+    if m == 0 {
+        panic!("Binomial mod 0");
+    }
+    let m = i64::try_from(m).expect("m is too big to factorize");
+    let congruences = factors(m)
+        .dedup_with_count()
+        .map(|(e, p)| {
+            let p = p as u64;
+            let result = if e == 1 {
+                lucas_binomial(n, k, p)
+            } else {
+                binomial_mod_prime_power(n, k, p, e as u32)
+            };
+            (result, p.pow(e as u32))
+        })
+        .collect::<Vec<_>>();
+    crt(&congruences).unwrap().0
+}
+
+/// The multinomial coefficient (k1 + k2 + ...)! / k1! k2! ..., the number
+/// of ways of splitting things into groups of the given sizes.
+///
+/// ```
+/// // The arrangements of the letters of MISSISSIPPI.
+/// assert_eq!(euler::multinomial(&[1, 4, 4, 2]), 34650.into());
+/// ```
+pub fn multinomial(ks: &[u64]) -> BigInt {
+    let mut n = 0;
+    let mut result = BigInt::one();
+    for &k in ks {
+        n += k;
+        result *= binomial_big(n, k);
+    }
+    result
+}
+
+/// The nth Catalan number C(2n, n) / (n + 1), which counts (among many
+/// other things) the ways of matching up n pairs of brackets.
+///
+/// ```
+/// let catalans = (0..8).map(euler::catalan).collect::<Vec<_>>();
+/// assert_eq!(catalans, [1, 1, 2, 5, 14, 42, 132, 429].map(Into::into));
+/// ```
+pub fn catalan(n: u64) -> BigInt {
+    binomial_big(2 * n, n) / (n + 1)
+}
+
+/// The unsigned Stirling number of the first kind [n k]: the number of
+/// permutations of n things with exactly k cycles.
+///
+/// ```
+/// assert_eq!(euler::stirling1(4, 2), 11.into());
+/// ```
+pub fn stirling1(n: usize, k: usize) -> BigInt {
+    // [n + 1, k] = n [n, k] + [n, k - 1]
+    stirling_table(n, k, |i, _| i)
+}
+
+/// The Stirling number of the second kind {n k}: the number of ways of
+/// splitting n things into k non-empty groups.
+///
+/// ```
+/// assert_eq!(euler::stirling2(4, 2), 7.into());
+/// ```
+pub fn stirling2(n: usize, k: usize) -> BigInt {
+    // {n + 1, k} = k {n, k} + {n, k - 1}
+    stirling_table(n, k, |_, j| j)
+}
+
+// Works through the rows of a triangle with
+// row[i + 1][j] = factor(i, j) × row[i][j] + row[i][j - 1],
+// which both kinds of Stirling number follow, keeping one row at a time.
+fn stirling_table(n: usize, k: usize, factor: impl Fn(usize, usize) -> usize) -> BigInt {
+    if k > n {
+        return BigInt::zero();
+    }
+    let mut row = vec![BigInt::zero(); k + 1];
+    row[0] = BigInt::one();
+    for i in 0..n {
+        for j in (1..=k.min(i + 1)).rev() {
+            let next = &row[j] * factor(i, j) + &row[j - 1];
+            row[j] = next;
+        }
+        row[0] = BigInt::zero();
+    }
+    row.swap_remove(k)
+}
+
+/// The nth Bell number, the number of ways of splitting n things into
+/// any number of non-empty groups, using the Bell triangle.
+///
+/// ```
+/// let bells = (0..8).map(euler::bell).collect::<Vec<_>>();
+/// assert_eq!(bells, [1, 1, 2, 5, 15, 52, 203, 877].map(Into::into));
+/// ```
+pub fn bell(n: usize) -> BigInt {
+    // Each row starts with the end of the last one, and each entry is
+    // the one before it plus the one above that. The rows start with
+    // the Bell numbers.
+    let mut row = vec![BigInt::one()];
+    for _ in 0..n {
+        let mut next = Vec::with_capacity(row.len() + 1);
+        next.push(row.last().unwrap().clone());
+        for above in &row {
+            let entry = next.last().unwrap() + above;
+            next.push(entry);
+        }
+        row = next;
+    }
+    row.swap_remove(0)
+}
+
+/// The number of derangements of n things: the permutations that leave
+/// nothing where it was.
+///
+/// ```
+/// let derangements = (0..7).map(euler::derangements).collect::<Vec<_>>();
+/// assert_eq!(derangements, [1, 0, 1, 2, 9, 44, 265].map(Into::into));
+/// ```
+pub fn derangements(n: u64) -> BigInt {
+    // D(n) = n D(n - 1) + (-1)^n
+    (1..=n).fold(BigInt::one(), |d, i| {
+        let d = d * i;
+        if i % 2 == 0 {
+            d + 1
+        } else {
+            d - 1
+        }
+    })
+}
+
+/// Factorials and their inverses mod a prime p, which make binomials
+/// mod p a couple of lookups. The tables grow as needed, up to p - 1.
+///
+/// ```
+/// use euler::Factorials;
+///
+/// let mut f = Factorials::new(1_000_000_007);
+/// assert_eq!(f.factorial(10), 3628800);
+/// assert_eq!(f.binomial(100_000, 3), 665_533_373);
+/// ```
+pub struct Factorials {
+    p: u64,
+    factorials: Vec<u64>,
+    inverses: Vec<u64>,
+}
+
+impl Factorials {
+    /// Gives nonsense if p isn't prime.
+    pub fn new(p: u64) -> Self {
+        Self {
+            p,
+            factorials: vec![1 % p],
+            inverses: vec![1 % p],
+        }
+    }
+
+    /// n! mod p.
+    ///
+    /// Panics if n ≥ p.
+    pub fn factorial(&mut self, n: u64) -> u64 {
+        self.extend(n);
+        self.factorials[n as usize]
+    }
+
+    /// The inverse of n! mod p.
+    ///
+    /// Panics if n ≥ p.
+    pub fn inverse_factorial(&mut self, n: u64) -> u64 {
+        self.extend(n);
+        self.inverses[n as usize]
+    }
+
+    /// C(n, k) mod p.
+    ///
+    /// Panics if n ≥ p; `lucas_binomial` handles those.
+    pub fn binomial(&mut self, n: u64, k: u64) -> u64 {
+        if k > n {
+            return 0;
+        }
+        let top = self.factorial(n);
+        let bottom = mul_mod(
+            self.inverse_factorial(k),
+            self.inverse_factorial(n - k),
+            self.p,
+        );
+        mul_mod(top, bottom, self.p)
+    }
+
+    fn extend(&mut self, n: u64) {
+        if n >= self.p {
+            panic!("{}! is 0 mod {}", n, self.p);
+        }
+        let old_len = self.factorials.len() as u64;
+        if n < old_len {
+            return;
+        }
+        // Double at least, so that asking for one more each time is
+        // still linear overall.
+        let new_len = (n + 1).max(2 * old_len).min(self.p);
+        for i in old_len..new_len {
+            let f = mul_mod(*self.factorials.last().unwrap(), i, self.p);
+            self.factorials.push(f);
+        }
+        // Invert the last one, then work back down.
+        self.inverses.resize(new_len as usize, 0);
+        let last = new_len as usize - 1;
+        self.inverses[last] = pow_mod(self.factorials[last], self.p - 2, self.p);
+        for i in (old_len as usize..last).rev() {
+            self.inverses[i] = mul_mod(self.inverses[i + 1], i as u64 + 1, self.p);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::ToPrimitive;
+
+    #[test]
+    fn test_exact() {
+        // Pascal's triangle.
+        let mut row = vec![1_u128];
+        for n in 0..130_u64 {
+            for (k, &c) in row.iter().enumerate() {
+                assert_eq!(binomial(n, k as u64), c, "C({}, {})", n, k);
+                assert_eq!(binomial_big(n, k as u64), c.into());
+            }
+            row = std::iter::once(1)
+                .chain(row.windows(2).map(|w| w[0] + w[1]))
+                .chain(std::iter::once(1))
+                .collect();
+        }
+        // The biggest central binomial that fits.
+        assert_eq!(binomial(130, 65), binomial_big(130, 65).to_u128().unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_big() {
+        binomial(140, 70);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_prime_power_too_big() {
+        // 3^16 is over 10^7.
+        binomial_mod(100, 50, 2 * 3_u64.pow(16));
+    }
+
+    #[test]
+    fn test_modular_brute_force() {
+        for n in 0..60 {
+            for k in 0..=n + 1 {
+                let exact = binomial_big(n, k);
+                for m in 1..100_u64 {
+                    let expected = (&exact % m).to_u64().unwrap();
+                    assert_eq!(binomial_mod(n, k, m), expected, "C({}, {}) mod {}", n, k, m);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_factorials() {
+        for p in [2, 3, 5, 7, 101] {
+            let mut f = Factorials::new(p);
+            for n in (0..p).rev() {
+                assert_eq!(mul_mod(f.factorial(n), f.inverse_factorial(n), p), 1 % p);
+                for k in 0..=n {
+                    assert_eq!(f.binomial(n, k), lucas_binomial(n, k, p));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stirling() {
+        // Each row of the first kind adds up to n!, and of the second kind
+        // to the Bell number.
+        let mut factorial = BigInt::one();
+        for n in 0..30 {
+            if n > 0 {
+                factorial *= n;
+            }
+            let first = (0..=n).map(|k| stirling1(n, k)).sum::<BigInt>();
+            assert_eq!(first, factorial);
+            let second = (0..=n).map(|k| stirling2(n, k)).sum::<BigInt>();
+            assert_eq!(second, bell(n));
+        }
+        assert_eq!(stirling1(0, 0), BigInt::one());
+        assert_eq!(stirling2(5, 0), BigInt::zero());
+        assert_eq!(stirling2(3, 7), BigInt::zero());
+    }
+
+    #[test]
+    fn test_derangements() {
+        // n! = Σ C(n, k) D(k), choosing which k things move.
+        let mut factorial = BigInt::one();
+        for n in 0..40 {
+            if n > 0 {
+                factorial *= n;
+            }
+            let total = (0..=n)
+                .map(|k| binomial_big(n, k) * derangements(k))
+                .sum::<BigInt>();
+            assert_eq!(total, factorial);
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use std::collections::VecDeque;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::One;
+use num_traits::Signed;
+use num_traits::Zero;
+
+use crate::sqrt_cf;
+
+/// Streams the decimal digits of a continued fraction [a0; a1, a2, ...],
+/// most significant first, starting with the digits of the integer part.
+/// A finite fraction goes on with zeros once its exact digits run out.
+///
+/// This is Gosper's algorithm. The digits so far and the terms so far
+/// are kept as a single Möbius transformation (ax + b) / (cx + d) of the
+/// rest of the fraction x, which is always at least 1. Whenever both
+/// ends of that range agree on the next digit it comes out; otherwise
+/// another term goes in. Everything stays exact, so the digits go on for
+/// as long as you like.
+///
+/// ```
+/// // 415/93 = [4; 2, 6, 7]
+/// let digits = euler::cf_digits(vec![4, 2, 6, 7]).take(8).collect::<Vec<_>>();
+/// assert_eq!(digits, [4, 4, 6, 2, 3, 6, 5, 5]);
+/// ```
+///
+/// There has to be at least a first term, a0, which should be
+/// non-negative, and the rest positive. Panics when it gets to the
+/// integer part if there are no terms, or the fraction is negative.
+pub fn cf_digits<I>(terms: I) -> CfDigits<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Into<BigInt>,
+{
+    CfDigits {
+        terms: Some(terms.into_iter()),
+        a: BigInt::one(),
+        b: BigInt::zero(),
+        c: BigInt::zero(),
+        d: BigInt::one(),
+        integer_part_done: false,
+        pending: VecDeque::new(),
+    }
+}
+
+pub struct CfDigits<I> {
+    // None once all the terms have been used up.
+    terms: Option<I>,
+    a: BigInt,
+    b: BigInt,
+    c: BigInt,
+    d: BigInt,
+    integer_part_done: bool,
+    pending: VecDeque<u32>,
+}
+
+impl<I> CfDigits<I>
+where
+    I: Iterator,
+    I::Item: Into<BigInt>,
+{
+    // The next digit, if the transformation has pinned it down.
+    fn ready_digit(&self) -> Option<BigInt> {
+        if self.c.is_zero() {
+            return None;
+        }
+        let at_infinity = self.a.div_floor(&self.c);
+        if self.terms.is_none() {
+            return Some(at_infinity);
+        }
+        let den = &self.c + &self.d;
+        if den.is_zero() {
+            return None;
+        }
+        let at_one = (&self.a + &self.b).div_floor(&den);
+        (at_infinity == at_one).then_some(at_infinity)
+    }
+
+    fn ingest(&mut self) {
+        let term = self.terms.as_mut().and_then(|terms| terms.next());
+        match term {
+            Some(t) => {
+                let t: BigInt = t.into();
+                let a = &self.a * &t + &self.b;
+                let c = &self.c * &t + &self.d;
+                self.b = std::mem::replace(&mut self.a, a);
+                self.d = std::mem::replace(&mut self.c, c);
+            }
+            None => self.terms = None,
+        }
+    }
+}
+
+impl<I> Iterator for CfDigits<I>
+where
+    I: Iterator,
+    I::Item: Into<BigInt>,
+{
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        while self.pending.is_empty() {
+            let Some(q) = self.ready_digit() else {
+                // Only an empty fraction runs out before pinning a digit.
+                if self.terms.is_none() {
+                    panic!("A continued fraction needs at least one term");
+                }
+                self.ingest();
+                continue;
+            };
+            // Take q off, and shift the next digit into the integer part.
+            self.a = (&self.a - &q * &self.c) * 10;
+            self.b = (&self.b - &q * &self.d) * 10;
+            if self.integer_part_done {
+                self.pending.push_back(q.try_into().unwrap());
+            } else {
+                if q.is_negative() {
+                    panic!("Can't stream the digits of a negative number");
+                }
+                let int = q.to_string();
+                self.pending
+                    .extend(int.bytes().map(|b| u32::from(b - b'0')));
+                self.integer_part_done = true;
+            }
+        }
+        self.pending.pop_front()
+    }
+}
+
+type Terms<T> = std::iter::Chain<std::vec::IntoIter<T>, std::iter::Cycle<std::vec::IntoIter<T>>>;
+
+/// Streams the decimal digits of √n, starting with its integer part.
+///
+/// ```
+/// let digits = euler::sqrt_digits(2).take(10).collect::<Vec<_>>();
+/// assert_eq!(digits, [1, 4, 1, 4, 2, 1, 3, 5, 6, 2]);
+/// let digits = euler::sqrt_digits(1024).take(4).collect::<Vec<_>>();
+/// assert_eq!(digits, [3, 2, 0, 0]);
+/// ```
+pub fn sqrt_digits(n: u64) -> CfDigits<Terms<i64>> {
+    let cf = sqrt_cf(n);
+    cf_digits(cf.prefix.into_iter().chain(cf.period.into_iter().cycle()))
+}
+
+/// Streams the decimal digits of e = [2; 1, 2, 1, 1, 4, 1, 1, 6, ...].
+///
+/// ```
+/// let digits = euler::e_digits().take(10).collect::<Vec<_>>();
+/// assert_eq!(digits, [2, 7, 1, 8, 2, 8, 1, 8, 2, 8]);
+/// ```
+pub fn e_digits() -> CfDigits<impl Iterator<Item = u64>> {
+    let rest = (1..).flat_map(|k| [1, 2 * k, 1]);
+    cf_digits(std::iter::once(2).chain(rest))
+}
+
+/// Streams the decimal digits of the golden ratio φ = [1; 1, 1, ...].
+///
+/// ```
+/// let digits = euler::phi_digits().take(10).collect::<Vec<_>>();
+/// assert_eq!(digits, [1, 6, 1, 8, 0, 3, 3, 9, 8, 8]);
+/// ```
+pub fn phi_digits() -> CfDigits<std::iter::Repeat<u64>> {
+    cf_digits(std::iter::repeat(1))
+}
+
+/// Streams the decimal digits of π, using Gibbons' unbounded spigot.
+///
+/// π has no simple continued fraction, so this works from a series
+/// instead, again kept as a Möbius transformation that produces digits
+/// once they're certain.
+///
+/// ```
+/// let digits = euler::pi_digits().take(10).collect::<Vec<_>>();
+/// assert_eq!(digits, [3, 1, 4, 1, 5, 9, 2, 6, 5, 3]);
+/// ```
+pub fn pi_digits() -> PiDigits {
+    PiDigits {
+        q: BigInt::one(),
+        r: BigInt::zero(),
+        t: BigInt::one(),
+        k: 1,
+        n: BigInt::from(3),
+        l: 3,
+    }
+}
+
+pub struct PiDigits {
+    q: BigInt,
+    r: BigInt,
+    t: BigInt,
+    k: u64,
+    n: BigInt,
+    l: u64,
+}
+
+impl Iterator for PiDigits {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            if &self.q * 4 + &self.r - &self.t < &self.n * &self.t {
+                let digit = std::mem::take(&mut self.n);
+                let r = (&self.r - &digit * &self.t) * 10;
+                self.n = (&self.q * 3 + &self.r) * 10 / &self.t - &digit * 10;
+                self.q *= 10;
+                self.r = r;
+                return Some(digit.try_into().unwrap());
+            }
+            let (k, l) = (self.k, self.l);
+            self.n = (&self.q * (7 * k + 2) + &self.r * l) / (&self.t * l);
+            self.r = (&self.q * 2 + &self.r) * l;
+            self.q *= k;
+            self.t *= l;
+            self.k += 1;
+            self.l += 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::isqrt;
+    use crate::Fixed;
+
+    fn first(digits: impl Iterator<Item = u32>, n: usize) -> String {
+        digits
+            .take(n)
+            .map(|d| char::from_digit(d, 10).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_constants() {
+        assert_eq!(
+            first(pi_digits(), 60),
+            "314159265358979323846264338327950288419716939937510582097494"
+        );
+        assert_eq!(
+            first(e_digits(), 60),
+            "271828182845904523536028747135266249775724709369995957496696"
+        );
+        assert_eq!(
+            first(phi_digits(), 60),
+            "161803398874989484820458683436563811772030917980576286213544"
+        );
+    }
+
+    #[test]
+    fn test_sqrt_matches_fixed() {
+        for n in 0..200 {
+            let expected = Fixed::from_int(n, 120).sqrt().to_string().replace('.', "");
+            let len = expected.len() - 5;
+            assert_eq!(first(sqrt_digits(n), len), expected[..len], "√{}", n);
+        }
+    }
+
+    #[test]
+    fn test_p80() {
+        // Problem 80: the digit sums of the first 100 digits of the
+        // irrational square roots up to 100.
+        let total: u32 = (1..=100_u64)
+            .filter(|n| isqrt(*n) * isqrt(*n) != *n)
+            .map(|n| sqrt_digits(n).take(100).sum::<u32>())
+            .sum();
+        assert_eq!(total, 40886);
+    }
+
+    #[test]
+    fn test_finite_fractions() {
+        assert_eq!(first(cf_digits(vec![0, 4]), 5), "02500");
+        assert_eq!(first(cf_digits(vec![123]), 5), "12300");
+        assert_eq!(first(cf_digits(vec![0, 3]), 5), "03333");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative() {
+        cf_digits(vec![-3, 1, 2]).next();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_no_terms() {
+        cf_digits(Vec::<u64>::new()).next();
+    }
+}
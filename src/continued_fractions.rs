@@ -0,0 +1,546 @@
+use std::collections::HashMap;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::One;
+use num_traits::Zero;
+
+use crate::isqrt;
+
+/// Expands p/q as a finite continued fraction [a0; a1, ..., an].
+///
+/// The integer part a0 is the floor of p/q, so it's negative if p/q is,
+/// but all the other terms are positive.
+///
+/// ```
+/// assert_eq!(euler::rational_cf(415, 93), [4, 2, 6, 7]);
+/// assert_eq!(euler::rational_cf(-7, 3), [-3, 1, 2]);
+/// ```
+///
+/// Panics if q is zero.
+pub fn rational_cf(p: i64, q: i64) -> Vec<i64> {
+    if q == 0 {
+        panic!("Can't expand {}/0", p);
+    }
+    let (mut p, mut q) = (p, q);
+    let mut terms = vec![];
+    while q != 0 {
+        let a = Integer::div_floor(&p, &q);
+        terms.push(a);
+        (p, q) = (q, p - a * q);
+    }
+    terms
+}
+
+/// A continued fraction that eventually repeats, like the expansion of
+/// any quadratic surd. If the period is empty, the fraction is finite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeriodicCf {
+    pub prefix: Vec<i64>,
+    pub period: Vec<i64>,
+}
+
+impl PeriodicCf {
+    /// Iterates over the terms of the fraction, which goes on forever
+    /// unless the period is empty.
+    pub fn terms(&self) -> impl Iterator<Item = i64> + '_ {
+        let period = self.period.iter().cycle();
+        self.prefix.iter().chain(period).copied()
+    }
+}
+
+/// Expands √n as a continued fraction. The prefix is the integer part,
+/// and the period ends with twice the integer part. A perfect square
+/// has an empty period.
+///
+/// ```
+/// let sqrt7 = euler::sqrt_cf(7);
+/// assert_eq!(sqrt7.prefix, [2]);
+/// assert_eq!(sqrt7.period, [1, 1, 1, 4]);
+/// ```
+pub fn sqrt_cf(n: u64) -> PeriodicCf {
+    let a0 = isqrt(n);
+    if a0 * a0 == n {
+        return PeriodicCf {
+            prefix: vec![a0 as i64],
+            period: vec![],
+        };
+    }
+    quadratic_cf(0, n, 1)
+}
+
+/// Expands the quadratic surd (p + √d) / q as a continued fraction.
+///
+/// Panics if q is zero or d is a perfect square.
+pub fn quadratic_cf(p: i64, d: u64, q: i64) -> PeriodicCf {
+    if q == 0 {
+        panic!("Can't expand a surd with denominator 0");
+    }
+    let (mut p, mut d, mut q) = (p as i128, d as i128, q as i128);
+    let sqrt_d = isqrt(d);
+    if sqrt_d * sqrt_d == d {
+        panic!("√{} is rational", d);
+    }
+
+    // The recurrence needs q to divide d - p², so scale everything up
+    // by |q| if necessary.
+    if (d - p * p) % q != 0 {
+        d *= q * q;
+        p *= q.abs();
+        q *= q.abs();
+    }
+    let sqrt_d = isqrt(d);
+
+    let mut terms = vec![];
+    let mut seen = HashMap::new();
+    loop {
+        if let Some(&start) = seen.get(&(p, q)) {
+            let period = terms.split_off(start);
+            return PeriodicCf {
+                prefix: terms,
+                period,
+            };
+        }
+        seen.insert((p, q), terms.len());
+
+        let a = surd_floor(p, q, sqrt_d);
+        terms.push(a as i64);
+        p = a * q - p;
+        q = (d - p * p) / q;
+    }
+}
+
+/// Computes floor((p + √d) / q), where sqrt_d = floor(√d) and √d is
+/// irrational.
+pub(crate) fn surd_floor(p: i128, q: i128, sqrt_d: i128) -> i128 {
+    if q > 0 {
+        (p + sqrt_d).div_euclid(q)
+    } else {
+        -(p + sqrt_d).div_euclid(-q) - 1
+    }
+}
+
+/// Iterates over the convergents of a continued fraction, as pairs of
+/// (numerator, denominator).
+///
+/// ```
+/// use num_bigint::BigInt;
+///
+/// let sqrt2 = euler::sqrt_cf(2);
+/// let (p, q) = euler::convergents(sqrt2.terms()).nth(3).unwrap();
+/// assert_eq!((p, q), (BigInt::from(17), BigInt::from(12)));
+/// ```
+pub fn convergents<I>(terms: I) -> Convergents<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Into<BigInt>,
+{
+    Convergents {
+        terms: terms.into_iter(),
+        h: (BigInt::zero(), BigInt::one()),
+        k: (BigInt::one(), BigInt::zero()),
+    }
+}
+
+pub struct Convergents<I> {
+    terms: I,
+    // The previous two numerators and denominators.
+    h: (BigInt, BigInt),
+    k: (BigInt, BigInt),
+}
+
+impl<I> Iterator for Convergents<I>
+where
+    I: Iterator,
+    I::Item: Into<BigInt>,
+{
+    type Item = (BigInt, BigInt);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let a: BigInt = self.terms.next()?.into();
+        let h = &a * &self.h.1 + &self.h.0;
+        let k = a * &self.k.1 + &self.k.0;
+        self.h = (std::mem::replace(&mut self.h.1, h.clone()), h.clone());
+        self.k = (std::mem::replace(&mut self.k.1, k.clone()), k.clone());
+        Some((h, k))
+    }
+}
+
+/// Finds the fraction closest to the value of a continued fraction,
+/// out of all the fractions whose denominator is at most max_den.
+/// This is either a convergent or a semiconvergent.
+///
+/// ```
+/// use num_bigint::BigInt;
+///
+/// // The best approximation to π with a two-digit denominator:
+/// let pi = [3, 7, 15, 1, 292, 1, 1, 1, 2];
+/// let (p, q) = euler::best_approximation(pi, 99);
+/// assert_eq!((p, q), (BigInt::from(311), BigInt::from(99)));
+/// ```
+///
+/// Panics if max_den is zero.
+pub fn best_approximation<I>(terms: I, max_den: u64) -> (BigInt, BigInt)
+where
+    I: IntoIterator,
+    I::Item: Into<BigInt>,
+{
+    if max_den == 0 {
+        panic!("Denominator bound must be positive");
+    }
+    let max_den = BigInt::from(max_den);
+    let mut terms = terms.into_iter().map(Into::into);
+
+    // Find the last convergent with a small enough denominator, and the
+    // term after it.
+    let (mut h0, mut h1) = (BigInt::zero(), BigInt::one());
+    let (mut k0, mut k1) = (BigInt::one(), BigInt::zero());
+    let mut reversed = vec![];
+    let a = loop {
+        let a: BigInt = match terms.next() {
+            Some(a) => a,
+            None => return (h1, k1),
+        };
+        let k = &a * &k1 + &k0;
+        if k > max_den {
+            break a;
+        }
+        (h0, h1) = (h1.clone(), &a * &h1 + h0);
+        (k0, k1) = (k1, k);
+        reversed.push(a);
+    };
+
+    // The best semiconvergent, if it beats the convergent.
+    let m = ((&max_den - &k0) / &k1).min(a.clone());
+    let twice_m = 2 * &m;
+    let use_semiconvergent = if twice_m != a {
+        twice_m > a
+    } else {
+        // The half rule: it depends on whether [a_k; ..., a_1] is less
+        // than [a_{k+2}; a_{k+3}, ...]. The first of these doesn't
+        // include a_0, and is infinite if there is no a_1.
+        reversed.remove(0);
+        reversed.reverse();
+        !reversed.is_empty() && cf_less_than(&reversed, terms)
+    };
+
+    if use_semiconvergent {
+        (h0 + &m * h1, k0 + m * k1)
+    } else {
+        (h1, k1)
+    }
+}
+
+/// Is the finite continued fraction xs less than the (possibly infinite)
+/// continued fraction ys? An empty ys counts as infinity.
+fn cf_less_than(xs: &[BigInt], mut ys: impl Iterator<Item = BigInt>) -> bool {
+    // Each level of the fraction flips the sense of the comparison.
+    let mut flipped = false;
+    for x in xs {
+        match ys.next() {
+            // The rest of ys is infinite, so bigger than the rest of xs.
+            None => return !flipped,
+            Some(y) if *x != y => return (*x < y) != flipped,
+            Some(_) => flipped = !flipped,
+        }
+    }
+    // Now the rest of xs is infinite.
+    ys.next().is_some() && flipped
+}
+
+/// Iterates over the Farey sequence of order n: all fractions a/b in
+/// lowest terms between 0/1 and 1/1 inclusive, with b <= n, in
+/// increasing order.
+///
+/// ```
+/// let f5 = euler::farey(5).collect::<Vec<_>>();
+/// assert_eq!(f5[..4], [(0, 1), (1, 5), (1, 4), (1, 3)]);
+/// assert_eq!(f5.len(), 11);
+/// ```
+///
+/// Panics if n is 0, since then there are no fractions at all.
+pub fn farey(n: u64) -> Farey {
+    if n == 0 {
+        panic!("Farey sequences start at order 1");
+    }
+    Farey {
+        n,
+        current: Some(((0, 1), (1, n))),
+    }
+}
+
+pub struct Farey {
+    n: u64,
+    current: Option<((u64, u64), (u64, u64))>,
+}
+
+impl Iterator for Farey {
+    type Item = (u64, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let ((a, b), (c, d)) = self.current?;
+        self.current = if b == 1 && a == 1 {
+            None
+        } else {
+            // The next term after a/b, c/d is (kc - a)/(kd - b), where k
+            // is as large as possible without the denominator passing n.
+            let k = (self.n + b) / d;
+            Some(((c, d), (k * c - a, k * d - b)))
+        };
+        Some((a, b))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+/// Finds the path from the root 1/1 of the Stern–Brocot tree down to
+/// p/q, as a list of runs of moves in the same direction.
+///
+/// ```
+/// use euler::Direction::*;
+/// assert_eq!(euler::stern_brocot_path(3, 7), [(Left, 2), (Right, 2)]);
+/// ```
+///
+/// Panics unless p and q are both positive.
+pub fn stern_brocot_path(p: u64, q: u64) -> Vec<(Direction, u64)> {
+    if p == 0 || q == 0 {
+        panic!("Stern-Brocot tree only contains positive fractions");
+    }
+    let g = p.gcd(&q);
+    let (mut p, mut q) = (p / g, q / g);
+
+    // This is Euclid's algorithm by repeated subtraction, so the run
+    // lengths are the continued fraction terms, except that the last
+    // one is one shorter.
+    let mut path = vec![];
+    while p != q {
+        if p > q {
+            let run = (p - 1) / q;
+            path.push((Direction::Right, run));
+            p -= run * q;
+        } else {
+            let run = (q - 1) / p;
+            path.push((Direction::Left, run));
+            q -= run * p;
+        }
+    }
+    path
+}
+
+/// Follows a path down the Stern–Brocot tree from 1/1, and returns the
+/// fraction at the end of it. Runs of length 0 don't go anywhere, so
+/// they're skipped.
+///
+/// ```
+/// use euler::Direction::*;
+/// assert_eq!(euler::stern_brocot_fraction(&[(Left, 2), (Right, 2)]), (3, 7));
+/// assert_eq!(euler::stern_brocot_fraction(&[(Left, 0), (Right, 3)]), (4, 1));
+/// ```
+pub fn stern_brocot_fraction(path: &[(Direction, u64)]) -> (u64, u64) {
+    // The fraction is the mediant of the bounds on either side.
+    let (mut left, mut right) = ((0, 1), (1, 0));
+    let mut current = (1, 1);
+    for &(dir, run) in path.iter().filter(|&&(_, run)| run > 0) {
+        match dir {
+            Direction::Left => {
+                right = (
+                    current.0 + (run - 1) * left.0,
+                    current.1 + (run - 1) * left.1,
+                )
+            }
+            Direction::Right => {
+                left = (
+                    current.0 + (run - 1) * right.0,
+                    current.1 + (run - 1) * right.1,
+                )
+            }
+        }
+        current = (left.0 + right.0, left.1 + right.1);
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rational_cf() {
+        assert_eq!(rational_cf(0, 5), [0]);
+        assert_eq!(rational_cf(5, 1), [5]);
+        assert_eq!(rational_cf(1, 3), [0, 3]);
+        assert_eq!(rational_cf(-1, 3), [-1, 1, 2]);
+        assert_eq!(rational_cf(7, -3), [-3, 1, 2]);
+
+        for p in -50..50 {
+            for q in 1..50 {
+                let (h, k) = convergents(rational_cf(p, q)).last().unwrap();
+                let g = p.gcd(&q);
+                assert_eq!((h, k), (BigInt::from(p / g), BigInt::from(q / g)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_cf() {
+        assert_eq!(sqrt_cf(2).period, [2]);
+        assert_eq!(sqrt_cf(13).period, [1, 1, 1, 1, 6]);
+        assert_eq!(sqrt_cf(16).period, []);
+        assert_eq!(sqrt_cf(16).terms().collect::<Vec<_>>(), [4]);
+
+        // Problem 64: 1322 continued fractions for N <= 10000 have an
+        // odd period.
+        let odd = (2..=10_000).filter(|&n| sqrt_cf(n).period.len() % 2 == 1);
+        assert_eq!(odd.count(), 1322);
+    }
+
+    #[test]
+    fn test_quadratic_cf() {
+        // The golden ratio (1 + √5) / 2.
+        let phi = quadratic_cf(1, 5, 2);
+        assert_eq!(phi.prefix, []);
+        assert_eq!(phi.period, [1]);
+
+        // (4 + √7) / 3 = 2.2152... = [2; 4, 1, 1, 1, 4, 1, 1, 1, ...]
+        let x = quadratic_cf(4, 7, 3);
+        assert_eq!(x.prefix, [2]);
+        assert_eq!(x.period, [4, 1, 1, 1]);
+
+        // -√2 = [-2; 1, 1, 2, 2, ...]
+        let x = quadratic_cf(0, 2, -1);
+        assert_eq!(x.prefix, [-2, 1, 1]);
+        assert_eq!(x.period, [2]);
+    }
+
+    #[test]
+    fn test_convergents_of_e() {
+        // Problem 65: the 100th convergent of e has numerator digit sum 272.
+        let e_terms = (0..).map(|k| match k {
+            0 => 2,
+            _ if k % 3 == 2 => 2 * (k + 1) / 3,
+            _ => 1,
+        });
+        let (h, _) = convergents(e_terms).nth(99).unwrap();
+        let digit_sum: u32 = h.to_string().bytes().map(|b| (b - b'0') as u32).sum();
+        assert_eq!(digit_sum, 272);
+    }
+
+    #[test]
+    fn test_best_approximation_brute_force() {
+        for (p, q) in [
+            (3, 7),
+            (13, 47),
+            (355, 113),
+            (1, 2),
+            (2, 3),
+            (89, 55),
+            (-17, 5),
+        ] {
+            for max_den in 1..60 {
+                let (h, k) = best_approximation(rational_cf(p, q), max_den);
+                let (h, k) = (i64::try_from(h).unwrap(), i64::try_from(k).unwrap());
+
+                // Compare |h/k - p/q| with the best brute force distance.
+                let dist = |a: i64, b: i64| ((a * q - p * b).abs(), b * q);
+                let best = (1..=max_den as i64)
+                    .map(|b| {
+                        let a = Integer::div_floor(&(p * b), &q);
+                        dist(a, b).min(dist(a + 1, b))
+                    })
+                    .min_by(|x, y| (x.0 * y.1).cmp(&(y.0 * x.1)))
+                    .unwrap();
+                let ours = dist(h, k);
+                assert!(k <= max_den as i64);
+                assert_eq!(
+                    ours.0 * best.1,
+                    best.0 * ours.1,
+                    "{}/{} max {}",
+                    p,
+                    q,
+                    max_den
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_best_approximation_sqrt() {
+        for n in (2..30_u64).filter(|n| isqrt(*n) * isqrt(*n) != *n) {
+            let x = (n as f64).sqrt();
+            for max_den in 1..200 {
+                let (h, k) = best_approximation(sqrt_cf(n).terms(), max_den);
+                let ours = (f64::from(i32::try_from(h).unwrap())
+                    / f64::from(i32::try_from(k).unwrap())
+                    - x)
+                    .abs();
+                for b in 1..=max_den {
+                    let a = (x * b as f64).round();
+                    let theirs = (a / b as f64 - x).abs();
+                    assert!(
+                        ours <= theirs + 1e-12,
+                        "√{} max {}: {}/{}",
+                        n,
+                        max_den,
+                        a,
+                        b
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_farey() {
+        // Problem 72 counts the fractions in the Farey sequence of order 8,
+        // without 0/1 and 1/1.
+        assert_eq!(farey(8).count() - 2, 21);
+        let f8 = farey(8).collect::<Vec<_>>();
+        for w in f8.windows(2) {
+            let ((a, b), (c, d)) = (w[0], w[1]);
+            assert_eq!(b * c - a * d, 1);
+        }
+        assert_eq!(farey(1).collect::<Vec<_>>(), [(0, 1), (1, 1)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_farey_order_zero() {
+        farey(0);
+    }
+
+    #[test]
+    fn test_stern_brocot() {
+        assert_eq!(stern_brocot_path(1, 1), []);
+        assert_eq!(stern_brocot_path(2, 1), [(Direction::Right, 1)]);
+        assert_eq!(stern_brocot_path(1, 2), [(Direction::Left, 1)]);
+        assert_eq!(
+            stern_brocot_path(5, 7),
+            [
+                (Direction::Left, 1),
+                (Direction::Right, 2),
+                (Direction::Left, 1)
+            ]
+        );
+        for p in 1..40 {
+            for q in 1..40 {
+                let g = p.gcd(&q);
+                let path = stern_brocot_path(p, q);
+                assert_eq!(stern_brocot_fraction(&path), (p / g, q / g));
+
+                // Empty runs in either direction make no difference.
+                let padded = path
+                    .iter()
+                    .flat_map(|&(dir, run)| {
+                        [(Direction::Left, 0), (dir, run), (Direction::Right, 0)]
+                    })
+                    .collect::<Vec<_>>();
+                assert_eq!(stern_brocot_fraction(&padded), (p / g, q / g));
+            }
+        }
+        assert_eq!(stern_brocot_fraction(&[(Direction::Right, 0)]), (1, 1));
+    }
+}
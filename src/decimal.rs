@@ -1,3 +1,9 @@
+use num_bigint::BigUint;
+use num_integer::Integer;
+
+use crate::modular::mul_mod;
+use crate::multiplicative_order;
+
 /// Determines whether a number is a palindrome in decimal.
 ///
 /// The naive algorithm would be to call .to_string() on the
@@ -78,6 +84,173 @@ pub const POWERS_OF_10: [usize; 20] = [
     10_000_000_000_000_000_000,
 ];
 
+/// The expansion of a fraction p/q in some base, split into the integer
+/// part, the digits before the repeating part, and the repetend, which
+/// repeats forever. For a terminating expansion the repetend is empty.
+///
+/// Displays with the repetend in brackets:
+///
+/// ```
+/// let e = euler::expand_fraction(1, 6, 10);
+/// assert_eq!(e.pre_period, [1]);
+/// assert_eq!(e.repetend, [6]);
+/// assert_eq!(e.to_string(), "0.1(6)");
+///
+/// assert_eq!(euler::expand_fraction(22, 7, 10).to_string(), "3.(142857)");
+/// assert_eq!(euler::expand_fraction(5, 4, 10).to_string(), "1.25");
+/// assert_eq!(euler::expand_fraction(1, 3, 2).to_string(), "0.(01)");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Expansion {
+    pub integer: u64,
+    pub pre_period: Vec<u32>,
+    pub repetend: Vec<u32>,
+    pub base: u32,
+}
+
+impl std::fmt::Display for Expansion {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let digit = |d: &u32| std::char::from_digit(*d, self.base).unwrap();
+        write!(f, "{}", BigUint::from(self.integer).to_str_radix(self.base))?;
+        if self.pre_period.is_empty() && self.repetend.is_empty() {
+            return Ok(());
+        }
+        write!(
+            f,
+            ".{}",
+            self.pre_period.iter().map(digit).collect::<String>()
+        )?;
+        if !self.repetend.is_empty() {
+            write!(
+                f,
+                "({})",
+                self.repetend.iter().map(digit).collect::<String>()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Works out the exact expansion of p/q in the given base.
+///
+/// The lengths come first, from a bit of number theory. Write q = st,
+/// where every prime factor of s divides the base, and t is coprime to
+/// it. Then the pre-period is as long as it takes for a power of the
+/// base to be a multiple of s, and the repetend is as long as the
+/// multiplicative order of the base mod t. After that it's just long
+/// division.
+///
+/// The repetend can have up to q - 1 digits, so this is for fractions
+/// with reasonable denominators. Use `cycle_length` to find out how long
+/// it'll be first.
+///
+/// Panics if q is 0, or the base is not in 2..=36.
+pub fn expand_fraction(p: u64, q: u64, base: u32) -> Expansion {
+    check_base(base);
+    // The lengths are only right for fractions in lowest terms. (The max
+    // leaves 0/0 for expansion_lengths to complain about.)
+    let g = p.gcd(&q).max(1);
+    let (p, q) = (p / g, q / g);
+    let (pre_len, cycle_len) = expansion_lengths(q, base);
+
+    let mut remainder = p % q;
+    let mut next_digit = || {
+        let n = remainder as u128 * base as u128;
+        remainder = (n % q as u128) as u64;
+        (n / q as u128) as u32
+    };
+    let pre_period = (0..pre_len).map(|_| next_digit()).collect();
+    let repetend = (0..cycle_len).map(|_| next_digit()).collect();
+    Expansion {
+        integer: p / q,
+        pre_period,
+        repetend,
+        base,
+    }
+}
+
+/// Finds the length of the repeating part of 1/q in the given base, or 0
+/// if it terminates. Any p/q in lowest terms repeats with the same
+/// length.
+///
+/// ```
+/// assert_eq!(euler::cycle_length(7, 10), 6);
+/// assert_eq!(euler::cycle_length(8, 10), 0);
+/// // Problem 26: 1/983 has the longest cycle for d < 1000.
+/// assert_eq!(euler::cycle_length(983, 10), 982);
+/// ```
+pub fn cycle_length(q: u64, base: u32) -> u64 {
+    check_base(base);
+    expansion_lengths(q, base).1
+}
+
+/// Writes out p/q in the given base, truncated to n digits after the
+/// point. Unlike `expand_fraction` this is happy with any denominator,
+/// and any number of digits.
+///
+/// ```
+/// assert_eq!(euler::fraction_digits(1, 7, 10, 10), "0.1428571428");
+/// assert_eq!(euler::fraction_digits(2, 3, 4, 10), "0.6666");
+/// assert_eq!(euler::fraction_digits(255, 16, 2, 16), "f.f0");
+/// assert_eq!(euler::fraction_digits(5, 2, 0, 10), "2");
+/// ```
+pub fn fraction_digits(p: u64, q: u64, n: usize, base: u32) -> String {
+    check_base(base);
+    if q == 0 {
+        panic!("Zero denominator");
+    }
+    let mut result = BigUint::from(p / q).to_str_radix(base);
+    if n > 0 {
+        result.push('.');
+    }
+    let mut remainder = p % q;
+    for _ in 0..n {
+        let x = remainder as u128 * base as u128;
+        remainder = (x % q as u128) as u64;
+        let d = (x / q as u128) as u32;
+        result.push(std::char::from_digit(d, base).unwrap());
+    }
+    result
+}
+
+fn check_base(base: u32) {
+    if !(2..=36).contains(&base) {
+        panic!("Invalid base: {}", base);
+    }
+}
+
+// The lengths of the pre-period and repetend of 1/q.
+fn expansion_lengths(q: u64, base: u32) -> (usize, u64) {
+    if q == 0 {
+        panic!("Zero denominator");
+    }
+    let base = base as u64;
+
+    // Split q into s, made of primes that divide the base, and t.
+    let mut t = q;
+    loop {
+        let g = t.gcd(&base);
+        if g == 1 {
+            break;
+        }
+        t /= g;
+    }
+    let s = q / t;
+
+    let mut pre_len = 0;
+    let mut power = 1 % s;
+    while power != 0 {
+        power = mul_mod(power, base, s);
+        pre_len += 1;
+    }
+    let cycle_len = if t == 1 {
+        0
+    } else {
+        multiplicative_order(base, t).unwrap()
+    };
+    (pre_len, cycle_len)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,4 +295,69 @@ mod tests {
 
         assert_eq!(count_decimal_digits(1234567890), 10);
     }
+
+    #[test]
+    fn test_expansion_matches_long_division() {
+        for base in [2, 3, 10, 12] {
+            for q in 1..300 {
+                for p in [1, q - 1, q + 1, 3 * q + 2] {
+                    let e = expand_fraction(p, q, base);
+                    let len = e.pre_period.len() + e.repetend.len();
+                    let digits = fraction_digits(p, q, len + 2 * e.repetend.len() + 5, base);
+                    let after_point = digits.split('.').nth(1).unwrap();
+                    // A terminating expansion goes on with zeros.
+                    let repetend = if e.repetend.is_empty() {
+                        vec![0]
+                    } else {
+                        e.repetend
+                    };
+                    let mut expected = e.pre_period.clone();
+                    let rest = after_point.len() - expected.len();
+                    expected.extend(repetend.iter().cycle().take(rest));
+                    let expected = expected
+                        .iter()
+                        .map(|d| std::char::from_digit(*d, base).unwrap())
+                        .collect::<String>();
+                    assert_eq!(after_point, expected, "{}/{} base {}", p, q, base);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_expansion_is_minimal() {
+        // Neither part can be shortened, so the pre-period and repetend
+        // must not end in the same digit, and the repetend has no
+        // smaller period.
+        for q in 1..500 {
+            let e = expand_fraction(1, q, 10);
+            assert_eq!(e.repetend.len() as u64, cycle_length(q, 10));
+            if let (Some(a), Some(b)) = (e.pre_period.last(), e.repetend.last()) {
+                assert_ne!(a, b, "1/{}", q);
+            }
+            let n = e.repetend.len();
+            for k in (1..n).filter(|k| n.is_multiple_of(*k)) {
+                assert_ne!(e.repetend[..n - k], e.repetend[k..], "1/{}", q);
+            }
+        }
+    }
+
+    #[test]
+    fn test_expansion_not_in_lowest_terms() {
+        let e = expand_fraction(3, 6, 10);
+        assert_eq!((e.integer, e.pre_period, e.repetend), (0, vec![5], vec![]));
+        assert_eq!(expand_fraction(14, 4, 10).to_string(), "3.5");
+        assert_eq!(expand_fraction(2, 6, 10), expand_fraction(1, 3, 10));
+        assert_eq!(expand_fraction(0, 7, 10).to_string(), "0");
+    }
+
+    #[test]
+    fn test_big_denominator() {
+        let q = (1 << 31) - 1;
+        let e = expand_fraction(1, q, 2);
+        assert_eq!(e.pre_period, []);
+        assert_eq!(e.repetend.len(), 31);
+        assert_eq!(fraction_digits(q - 1, q, 3, 10), "0.999");
+        assert_eq!(e.to_string(), format!("0.({:031b})", 1));
+    }
 }
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use num_bigint::BigInt;
+use num_traits::One;
+use num_traits::Zero;
+
+/// Describes a property of numbers that can be checked one decimal digit
+/// at a time, from the most significant end, keeping only a small state
+/// along the way. `count_up_to` and `sum_up_to` use it to find how many
+/// numbers up to some limit have the property, without looking at each
+/// number separately.
+///
+/// The state should hold just enough to decide the property: the digit
+/// sum, say, rather than the digits themselves. The fewer distinct
+/// states there are, the faster the count.
+///
+/// Leading zeros are never fed to the state machine, so the digits of
+/// 42 are just 4 and 2, even when counting up to 1000.
+pub trait DigitDp {
+    type State: Clone + Eq + Hash;
+
+    /// The state before any digits have been seen.
+    fn initial(&self) -> Self::State;
+
+    /// The state after appending the next digit, or None if no number
+    /// starting with these digits can have the property.
+    fn transition(&self, state: &Self::State, digit: u32) -> Option<Self::State>;
+
+    /// Does a number that ends in this state have the property?
+    fn accept(&self, state: &Self::State) -> bool;
+}
+
+/// Counts the numbers n in 1..=limit for which the digit DP accepts n.
+/// The limit is a decimal string, so it can be as large as you like.
+///
+/// ```
+/// use euler::DigitDp;
+///
+/// // Numbers whose digits add up to 10.
+/// struct DigitSum10;
+///
+/// impl DigitDp for DigitSum10 {
+///     type State = u32;
+///
+///     fn initial(&self) -> u32 {
+///         0
+///     }
+///
+///     fn transition(&self, sum: &u32, digit: u32) -> Option<u32> {
+///         Some(sum + digit).filter(|&s| s <= 10)
+///     }
+///
+///     fn accept(&self, sum: &u32) -> bool {
+///         *sum == 10
+///     }
+/// }
+///
+/// assert_eq!(euler::count_up_to(&DigitSum10, "100"), 9.into());
+/// assert_eq!(euler::count_up_to(&DigitSum10, "1000"), 63.into());
+/// assert_eq!(euler::sum_up_to(&DigitSum10, "100"), 495.into());
+/// ```
+///
+/// Panics if the limit isn't a string of decimal digits.
+pub fn count_up_to<D: DigitDp>(dp: &D, limit: &str) -> BigInt {
+    totals_up_to(dp, limit).0
+}
+
+/// Adds up the numbers n in 1..=limit for which the digit DP accepts n.
+///
+/// Panics if the limit isn't a string of decimal digits.
+pub fn sum_up_to<D: DigitDp>(dp: &D, limit: &str) -> BigInt {
+    totals_up_to(dp, limit).1
+}
+
+// How many numbers reach a state, and what they add up to.
+#[derive(Clone, Default)]
+struct Totals {
+    count: BigInt,
+    sum: BigInt,
+}
+
+impl Totals {
+    // Just the number with no digits yet.
+    fn empty() -> Self {
+        Self {
+            count: BigInt::one(),
+            sum: BigInt::zero(),
+        }
+    }
+
+    // Folds in some numbers that have had the digit d appended.
+    fn add_appended(&mut self, other: &Totals, d: u32) {
+        self.count += &other.count;
+        self.sum += &other.sum * 10 + &other.count * d;
+    }
+}
+
+fn totals_up_to<D: DigitDp>(dp: &D, limit: &str) -> (BigInt, BigInt) {
+    let limit = limit
+        .chars()
+        .map(|c| c.to_digit(10).expect("Limit must be a decimal number"))
+        .skip_while(|&d| d == 0)
+        .collect::<Vec<_>>();
+
+    // We go along the limit's digits, keeping track of two kinds of
+    // number: the ones that are already below the limit, so their
+    // remaining digits are free, and the single "tight" number that
+    // matches the limit so far.
+    let initial = dp.initial();
+    let mut below: HashMap<D::State, Totals> = HashMap::new();
+    let mut tight = Some(initial.clone());
+    let mut tight_value = BigInt::zero();
+
+    for (i, &limit_digit) in limit.iter().enumerate() {
+        let mut next: HashMap<D::State, Totals> = HashMap::new();
+        for (state, totals) in &below {
+            for d in 0..10 {
+                if let Some(s) = dp.transition(state, d) {
+                    next.entry(s).or_default().add_appended(totals, d);
+                }
+            }
+        }
+
+        // Numbers with fewer digits than the limit start here.
+        if i > 0 {
+            for d in 1..10 {
+                if let Some(s) = dp.transition(&initial, d) {
+                    next.entry(s).or_default().add_appended(&Totals::empty(), d);
+                }
+            }
+        }
+
+        // The tight number can drop below the limit with a smaller digit.
+        if let Some(state) = &tight {
+            let tight_totals = Totals {
+                count: BigInt::one(),
+                sum: tight_value.clone(),
+            };
+            let first = if i == 0 { 1 } else { 0 };
+            for d in first..limit_digit {
+                if let Some(s) = dp.transition(state, d) {
+                    next.entry(s).or_default().add_appended(&tight_totals, d);
+                }
+            }
+            tight = dp.transition(state, limit_digit);
+        }
+        tight_value = tight_value * 10 + limit_digit;
+        below = next;
+    }
+
+    let mut result = Totals::default();
+    for (state, totals) in &below {
+        if dp.accept(state) {
+            result.count += &totals.count;
+            result.sum += &totals.sum;
+        }
+    }
+    if let Some(state) = tight {
+        if !limit.is_empty() && dp.accept(&state) {
+            result.count += 1;
+            result.sum += tight_value;
+        }
+    }
+    (result.count, result.sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::digits_msb;
+
+    // Digit sums divisible by m.
+    struct DigitSumMod(u32);
+
+    impl DigitDp for DigitSumMod {
+        type State = u32;
+
+        fn initial(&self) -> u32 {
+            0
+        }
+
+        fn transition(&self, sum: &u32, digit: u32) -> Option<u32> {
+            Some((sum + digit) % self.0)
+        }
+
+        fn accept(&self, sum: &u32) -> bool {
+            *sum == 0
+        }
+    }
+
+    // No two neighbouring digits are the same.
+    struct NoRepeats;
+
+    impl DigitDp for NoRepeats {
+        type State = Option<u32>;
+
+        fn initial(&self) -> Option<u32> {
+            None
+        }
+
+        fn transition(&self, prev: &Option<u32>, digit: u32) -> Option<Option<u32>> {
+            (*prev != Some(digit)).then_some(Some(digit))
+        }
+
+        fn accept(&self, _: &Option<u32>) -> bool {
+            true
+        }
+    }
+
+    fn brute_force<D: DigitDp>(dp: &D, limit: u64) -> (BigInt, BigInt) {
+        let accepted = (1..=limit).filter(|&n| {
+            let mut state = Some(dp.initial());
+            for d in digits_msb(n, 10) {
+                state = state.and_then(|s| dp.transition(&s, d));
+            }
+            state.is_some_and(|s| dp.accept(&s))
+        });
+        let (count, sum) = accepted.fold((0_u64, 0_u64), |(c, s), n| (c + 1, s + n));
+        (count.into(), sum.into())
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for limit in [
+            0, 1, 9, 10, 11, 99, 100, 101, 555, 1000, 4321, 9999, 10_000, 12_345,
+        ] {
+            let text = limit.to_string();
+            for m in 1..5 {
+                let dp = DigitSumMod(m);
+                assert_eq!(
+                    totals_up_to(&dp, &text),
+                    brute_force(&dp, limit),
+                    "{} {}",
+                    m,
+                    limit
+                );
+            }
+            assert_eq!(
+                totals_up_to(&NoRepeats, &text),
+                brute_force(&NoRepeats, limit)
+            );
+        }
+    }
+
+    #[test]
+    fn test_leading_zeros() {
+        assert_eq!(
+            count_up_to(&NoRepeats, "00100"),
+            count_up_to(&NoRepeats, "100")
+        );
+        assert_eq!(count_up_to(&NoRepeats, ""), BigInt::zero());
+        assert_eq!(count_up_to(&NoRepeats, "000"), BigInt::zero());
+    }
+
+    #[test]
+    fn test_huge_limit() {
+        struct DigitSum3;
+
+        impl DigitDp for DigitSum3 {
+            type State = u32;
+
+            fn initial(&self) -> u32 {
+                0
+            }
+
+            fn transition(&self, sum: &u32, digit: u32) -> Option<u32> {
+                Some(sum + digit).filter(|&s| s <= 3)
+            }
+
+            fn accept(&self, sum: &u32) -> bool {
+                *sum == 3
+            }
+        }
+
+        // Up to 10^100, that's 3 units spread over 100 places: C(102, 3).
+        let limit = format!("1{}", "0".repeat(100));
+        assert_eq!(count_up_to(&DigitSum3, &limit), BigInt::from(171_700));
+
+        // Every digit sum is divisible by 1.
+        let all = count_up_to(&DigitSumMod(1), &limit);
+        assert_eq!(all.to_string(), limit);
+    }
+}
@@ -0,0 +1,214 @@
+use num_integer::Integer;
+use num_traits::FromPrimitive;
+use num_traits::ToPrimitive;
+
+/// Iterates over the digits of n in the given base, starting with the
+/// least significant digit. Zero has a single digit, 0.
+///
+/// Works for any integer type, including BigInt, but only for
+/// non-negative numbers.
+///
+/// ```
+/// let ds = euler::digits(1234, 10).collect::<Vec<_>>();
+/// assert_eq!(ds, [4, 3, 2, 1]);
+/// let bits = euler::digits(6_u8, 2).collect::<Vec<_>>();
+/// assert_eq!(bits, [0, 1, 1]);
+/// ```
+///
+/// Panics if n is negative or base is less than 2.
+pub fn digits<T>(n: T, base: u32) -> Digits<T>
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    if n < T::zero() {
+        panic!("Can only take digits of non-negative numbers");
+    }
+    if base < 2 {
+        panic!("Invalid base: {}", base);
+    }
+    let base = T::from_u32(base).expect("base doesn't fit in the number type");
+    Digits { n: Some(n), base }
+}
+
+pub struct Digits<T> {
+    n: Option<T>,
+    base: T,
+}
+
+impl<T> Iterator for Digits<T>
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.n.take()?;
+        let (q, r) = n.div_rem(&self.base);
+        if !q.is_zero() {
+            self.n = Some(q);
+        }
+        r.to_u32()
+    }
+}
+
+/// Returns the digits of n in the given base, starting with the most
+/// significant.
+///
+/// ```
+/// let ds = euler::digits_msb(1234, 10).collect::<Vec<_>>();
+/// assert_eq!(ds, [1, 2, 3, 4]);
+/// ```
+pub fn digits_msb<T>(n: T, base: u32) -> std::iter::Rev<std::vec::IntoIter<u32>>
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    digits(n, base).collect::<Vec<_>>().into_iter().rev()
+}
+
+/// Counts the digits of n in the given base.
+pub fn num_digits<T>(n: T, base: u32) -> usize
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    digits(n, base).count()
+}
+
+/// Adds up the digits of n in the given base.
+pub fn digit_sum<T>(n: T, base: u32) -> u64
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    digits(n, base).map(u64::from).sum()
+}
+
+/// Multiplies together the digits of n in the given base.
+pub fn digit_product<T>(n: T, base: u32) -> T
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    digits(n, base).fold(T::one(), |p, d| p * T::from_u32(d).unwrap())
+}
+
+/// Builds a number from its digits in the given base, most significant
+/// first.
+///
+/// ```
+/// let n: u64 = euler::from_digits([1, 0, 1, 1], 2);
+/// assert_eq!(n, 11);
+/// ```
+pub fn from_digits<T, I>(digits: I, base: u32) -> T
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+    I: IntoIterator<Item = u32>,
+{
+    let base = T::from_u32(base).unwrap();
+    digits
+        .into_iter()
+        .fold(T::zero(), |n, d| n * base.clone() + T::from_u32(d).unwrap())
+}
+
+/// Reverses the digits of n in the given base. Trailing zeros become
+/// leading zeros, and so disappear: reversing 120 gives 21.
+///
+/// ```
+/// assert_eq!(euler::reverse_digits(1234, 10), 4321);
+/// assert_eq!(euler::reverse_digits(0b1101_u32, 2), 0b1011);
+/// ```
+pub fn reverse_digits<T>(n: T, base: u32) -> T
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    from_digits(digits(n, base), base)
+}
+
+/// Rotates the digits of n left by k places in the given base, so that
+/// the first k digits move to the end. As with reversal, any leading
+/// zeros that result are lost.
+///
+/// ```
+/// assert_eq!(euler::rotate_digits(12345, 2, 10), 34512);
+/// assert_eq!(euler::rotate_digits(197, 1, 10), 971);
+/// ```
+pub fn rotate_digits<T>(n: T, k: usize, base: u32) -> T
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    let mut ds = digits_msb(n, base).collect::<Vec<_>>();
+    let len = ds.len();
+    ds.rotate_left(k % len);
+    from_digits(ds, base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_zero() {
+        assert_eq!(digits(0, 10).collect::<Vec<_>>(), [0]);
+        assert_eq!(num_digits(0, 2), 1);
+        assert_eq!(digit_sum(0, 10), 0);
+        assert_eq!(digit_product(0, 10), 0);
+        assert_eq!(reverse_digits(0, 10), 0);
+    }
+
+    #[test]
+    fn test_bigint() {
+        // Problem 16: the digit sum of 2^1000 is 1366.
+        let n: BigInt = BigInt::from(1) << 1000;
+        assert_eq!(digit_sum(n.clone(), 10), 1366);
+        assert_eq!(num_digits(n.clone(), 10), 302);
+        assert_eq!(num_digits(n.clone(), 2), 1001);
+
+        let s = n.to_string();
+        let msb = digits_msb(n.clone(), 10).map(|d| char::from_digit(d, 10).unwrap());
+        assert_eq!(msb.collect::<String>(), s);
+        let reversed: BigInt = reverse_digits(n, 10);
+        assert_eq!(reversed.to_string(), s.chars().rev().collect::<String>());
+    }
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(rotate_digits(123, 0, 10), 123);
+        assert_eq!(rotate_digits(123, 3, 10), 123);
+        assert_eq!(rotate_digits(123, 4, 10), 231);
+        assert_eq!(rotate_digits(102, 1, 10), 21);
+        assert_eq!(rotate_digits(0b110_u8, 1, 2), 0b101);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative() {
+        digits(-1, 10).count();
+    }
+
+    proptest! {
+        #[test]
+        fn digits_match_formatting(n: u64, base in 2..=36_u32) {
+            let expected = num_bigint::BigUint::from(n).to_str_radix(base);
+            let actual = digits_msb(n, base)
+                .map(|d| char::from_digit(d, base).unwrap())
+                .collect::<String>();
+            prop_assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn from_digits_round_trip(n: u64, base in 2..=1000_u32) {
+            prop_assert_eq!(from_digits::<u64, _>(digits_msb(n, base), base), n);
+            let lsb = digits(n, base).collect::<Vec<_>>();
+            prop_assert_eq!(from_digits::<u64, _>(lsb.into_iter().rev(), base), n);
+        }
+
+        #[test]
+        fn reverse_twice(n in 0..1_000_000_000_i64) {
+            // Reversing twice only loses trailing zeros.
+            let mut m = n;
+            while m > 0 && m % 10 == 0 {
+                m /= 10;
+            }
+            prop_assert_eq!(reverse_digits(reverse_digits(n, 10), 10), m);
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+
+use crate::Rational;
+
+/// Results of powers are thrown away if the numerator or denominator
+/// would need more bits than this, to stop expressions like 9^(9^9)
+/// taking over the search.
+const MAX_POW_BITS: u64 = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    /// Joins the digits of two numbers, e.g., 1 ‖ 2 = 12. Only allowed
+    /// on the original numbers or on other concatenations.
+    Concat,
+    /// Raises to an integer power.
+    Pow,
+}
+
+impl Op {
+    fn is_commutative(self) -> bool {
+        matches!(self, Op::Add | Op::Mul)
+    }
+
+    fn apply(self, lhs: &Rational, rhs: &Rational) -> Option<Rational> {
+        match self {
+            Op::Add => Some(lhs + rhs),
+            Op::Sub => Some(lhs - rhs),
+            Op::Mul => Some(lhs * rhs),
+            Op::Div => lhs.checked_div(rhs),
+            Op::Concat => {
+                let (a, b) = (lhs.to_i64()?, rhs.to_i64()?);
+                if a <= 0 || b < 0 {
+                    return None;
+                }
+                let shift = 10_i64.checked_pow(b.checked_ilog10().unwrap_or(0) + 1)?;
+                Some(Rational::from(a.checked_mul(shift)?.checked_add(b)?))
+            }
+            Op::Pow => {
+                let exp = i32::try_from(rhs.to_i64()?).ok()?;
+                let bits = lhs.numer().bits().max(lhs.denom().bits());
+                // 0, 1 and -1 stay small whatever the exponent.
+                let stays_small = lhs.is_integer() && bits <= 1;
+                if !stays_small && bits * exp.unsigned_abs() as u64 > MAX_POW_BITS {
+                    return None;
+                }
+                lhs.pow(exp)
+            }
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Sub => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+            Op::Concat => "",
+            Op::Pow => "^",
+        }
+    }
+}
+
+/// An expression tree. Subtrees are shared between the witnesses for
+/// different values, which is why they're reference counted.
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Num(i64),
+    Bin(Op, Rc<Expr>, Rc<Expr>),
+}
+
+impl Expr {
+    pub fn eval(&self) -> Option<Rational> {
+        match self {
+            Expr::Num(n) => Some(Rational::from(*n)),
+            Expr::Bin(op, lhs, rhs) => op.apply(&lhs.eval()?, &rhs.eval()?),
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Num(n) => write!(f, "{}", n),
+            Expr::Bin(Op::Concat, lhs, rhs) => write!(f, "{}{}", lhs, rhs),
+            Expr::Bin(op, lhs, rhs) => {
+                // Fully parenthesized, apart from the outermost level.
+                let show = |f: &mut std::fmt::Formatter<'_>, e: &Expr| match e {
+                    Expr::Bin(op, _, _) if *op != Op::Concat => write!(f, "({})", e),
+                    _ => write!(f, "{}", e),
+                };
+                show(f, lhs)?;
+                write!(f, " {} ", op.symbol())?;
+                show(f, rhs)
+            }
+        }
+    }
+}
+
+/// Searches for all the values that can be made by combining a list of
+/// numbers with a set of arithmetic operations, as in the "24 game" or
+/// the numbers round of Countdown.
+///
+/// ```
+/// use euler::{ExprSearch, Op, Rational};
+///
+/// let search = ExprSearch::new(&[Op::Add, Op::Sub, Op::Mul, Op::Div]);
+/// let reachable = search.search(&[1, 5, 5, 5]);
+/// let witness = reachable.witness(&Rational::from(24)).unwrap();
+/// assert_eq!(witness.eval(), Some(Rational::from(24)));
+/// println!("24 = {}", witness); // e.g., (5 - (1 / 5)) * 5
+/// ```
+pub struct ExprSearch {
+    ops: Vec<Op>,
+    use_all_numbers: bool,
+}
+
+impl ExprSearch {
+    pub fn new(ops: &[Op]) -> Self {
+        Self {
+            ops: ops.to_vec(),
+            use_all_numbers: true,
+        }
+    }
+
+    /// By default, every number has to be used exactly once. Turning
+    /// this off allows any non-empty subset of the numbers, like in
+    /// Countdown.
+    pub fn use_all_numbers(mut self, use_all: bool) -> Self {
+        self.use_all_numbers = use_all;
+        self
+    }
+
+    /// Finds every reachable value, along with one expression for each.
+    ///
+    /// Panics if given more than 20 numbers, which would take forever
+    /// anyway.
+    pub fn search(&self, nums: &[i64]) -> Reachable {
+        let n = nums.len();
+        if n > 20 {
+            panic!("Too many numbers for an expression search: {}", n);
+        }
+        let full = (1_usize << n) - 1;
+
+        // values[mask] holds everything reachable using exactly the numbers
+        // in mask, and literals[mask] the subset of those that are just
+        // concatenations of the numbers.
+        let mut values: Vec<HashMap<Rational, Rc<Expr>>> = vec![HashMap::new(); full + 1];
+        let mut literals: Vec<HashMap<Rational, Rc<Expr>>> = vec![HashMap::new(); full + 1];
+        let concat = self.ops.contains(&Op::Concat);
+
+        for mask in 1..=full {
+            let mut found = HashMap::new();
+            let mut found_literals = HashMap::new();
+            if mask.is_power_of_two() {
+                let num = nums[mask.trailing_zeros() as usize];
+                found.insert(Rational::from(num), Rc::new(Expr::Num(num)));
+                found_literals = found.clone();
+            }
+
+            // Split mask into non-empty halves lhs and rhs.
+            let mut lhs = (mask - 1) & mask;
+            while lhs > 0 {
+                let rhs = mask ^ lhs;
+                for &op in &self.ops {
+                    if op.is_commutative() && lhs > rhs {
+                        continue;
+                    }
+                    let (sources, dest) = if op == Op::Concat {
+                        (&literals, &mut found_literals)
+                    } else {
+                        (&values, &mut found)
+                    };
+                    for (a, a_expr) in &sources[lhs] {
+                        for (b, b_expr) in &sources[rhs] {
+                            if let Some(c) = op.apply(a, b) {
+                                dest.entry(c).or_insert_with(|| {
+                                    Rc::new(Expr::Bin(op, a_expr.clone(), b_expr.clone()))
+                                });
+                            }
+                        }
+                    }
+                }
+                lhs = (lhs - 1) & mask;
+            }
+
+            if concat {
+                for (value, expr) in &found_literals {
+                    found.entry(value.clone()).or_insert_with(|| expr.clone());
+                }
+            }
+            values[mask] = found;
+            literals[mask] = found_literals;
+        }
+
+        let mut reachable = HashMap::new();
+        if self.use_all_numbers {
+            reachable = std::mem::take(&mut values[full]);
+        } else {
+            for found in values {
+                for (value, expr) in found {
+                    reachable.entry(value).or_insert(expr);
+                }
+            }
+        }
+        Reachable { values: reachable }
+    }
+}
+
+pub struct Reachable {
+    values: HashMap<Rational, Rc<Expr>>,
+}
+
+impl Reachable {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn contains(&self, value: &Rational) -> bool {
+        self.values.contains_key(value)
+    }
+
+    /// Returns an expression that evaluates to the value, if there is one.
+    pub fn witness(&self, value: &Rational) -> Option<&Expr> {
+        self.values.get(value).map(|e| e.as_ref())
+    }
+
+    /// Iterates over all the reachable values, in no particular order.
+    pub fn values(&self) -> impl Iterator<Item = &Rational> {
+        self.values.keys()
+    }
+
+    /// Counts how many of 1, 2, 3, ... are reachable before the first
+    /// one that isn't.
+    pub fn consecutive_from_one(&self) -> usize {
+        (1..)
+            .take_while(|&n| self.contains(&Rational::from(n)))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    const ARITHMETIC: [Op; 4] = [Op::Add, Op::Sub, Op::Mul, Op::Div];
+
+    // The classic way of solving the 24 game: pick any two numbers,
+    // replace them with the result of an operation, and recurse.
+    fn brute_force(nums: Vec<Rational>, results: &mut HashSet<Rational>) {
+        if nums.len() == 1 {
+            results.insert(nums[0].clone());
+            return;
+        }
+        for i in 0..nums.len() {
+            for j in 0..nums.len() {
+                if i == j {
+                    continue;
+                }
+                for op in ARITHMETIC {
+                    if let Some(c) = op.apply(&nums[i], &nums[j]) {
+                        let mut rest = nums
+                            .iter()
+                            .enumerate()
+                            .filter(|&(k, _)| k != i && k != j)
+                            .map(|(_, x)| x.clone())
+                            .collect::<Vec<_>>();
+                        rest.push(c);
+                        brute_force(rest, results);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        for nums in [
+            vec![1, 2, 3, 4],
+            vec![1, 5, 5, 5],
+            vec![3, 3, 8, 8],
+            vec![2, 7],
+            vec![6],
+        ] {
+            let reachable = ExprSearch::new(&ARITHMETIC).search(&nums);
+            let mut expected = HashSet::new();
+            brute_force(
+                nums.iter().map(|&n| Rational::from(n)).collect(),
+                &mut expected,
+            );
+            let actual = reachable.values().cloned().collect::<HashSet<_>>();
+            assert_eq!(actual, expected, "{:?}", nums);
+        }
+    }
+
+    #[test]
+    fn test_witnesses_evaluate_correctly() {
+        let ops = [Op::Add, Op::Sub, Op::Mul, Op::Div, Op::Concat, Op::Pow];
+        let reachable = ExprSearch::new(&ops).search(&[2, 3, 4, 5]);
+        for value in reachable.values() {
+            let witness = reachable.witness(value).unwrap();
+            assert_eq!(witness.eval().as_ref(), Some(value), "{}", witness);
+        }
+    }
+
+    #[test]
+    fn test_24_game() {
+        let search = ExprSearch::new(&ARITHMETIC);
+        let target = Rational::from(24);
+        assert!(search.search(&[3, 3, 8, 8]).contains(&target));
+        assert!(search.search(&[4, 7, 8, 8]).contains(&target));
+        assert!(!search.search(&[1, 1, 1, 1]).contains(&target));
+    }
+
+    #[test]
+    fn test_countdown() {
+        let search = ExprSearch::new(&ARITHMETIC);
+        let nums = [25, 50, 75, 100, 3, 6];
+        assert!(!search.search(&nums[..2]).contains(&Rational::from(50)));
+
+        let search = search.use_all_numbers(false);
+        let reachable = search.search(&nums);
+        assert!(reachable.contains(&Rational::from(50)));
+        assert!(reachable.contains(&Rational::from(952)));
+        let witness = reachable.witness(&Rational::from(952)).unwrap();
+        assert_eq!(witness.eval(), Some(Rational::from(952)));
+    }
+
+    #[test]
+    fn test_concat_and_pow() {
+        let reachable = ExprSearch::new(&[Op::Concat]).search(&[1, 2]);
+        let mut values = reachable
+            .values()
+            .map(|v| v.to_i64().unwrap())
+            .collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(values, [12, 21]);
+
+        let reachable = ExprSearch::new(&[Op::Add, Op::Concat]).search(&[1, 2, 3]);
+        assert!(reachable.contains(&Rational::from(123)));
+        let witness = reachable.witness(&Rational::from(15)).unwrap();
+        assert_eq!(witness.to_string(), "12 + 3");
+
+        // Concatenation only applies to the original numbers.
+        let reachable = ExprSearch::new(&[Op::Add, Op::Concat]).search(&[1, 1, 5]);
+        assert!(reachable.contains(&Rational::from(115)));
+        assert!(!reachable.contains(&Rational::from(25))); // (1 + 1) ‖ 5
+
+        let reachable = ExprSearch::new(&[Op::Pow]).search(&[2, 3]);
+        assert!(reachable.contains(&Rational::from(8)));
+        assert!(reachable.contains(&Rational::from(9)));
+
+        let reachable = ExprSearch::new(&[Op::Pow, Op::Sub]).search(&[2, 1, 3]);
+        assert!(reachable.contains(&Rational::new(1, 4))); // 2 ^ (1 - 3)
+
+        // Huge exponents are fine when the result stays small.
+        let reachable = ExprSearch::new(&[Op::Pow]).search(&[1, 1000]);
+        assert!(reachable.contains(&Rational::from(1)));
+        let reachable = ExprSearch::new(&[Op::Pow, Op::Sub]).search(&[0, 1, 999]);
+        assert!(reachable.contains(&Rational::from(-1))); // (0 - 1) ^ 999
+    }
+
+    #[test]
+    fn test_consecutive() {
+        // From problem 93: {1, 2, 3, 4} makes 1 to 28.
+        let reachable = ExprSearch::new(&ARITHMETIC).search(&[1, 2, 3, 4]);
+        assert_eq!(reachable.consecutive_from_one(), 28);
+    }
+}
@@ -1,20 +1,373 @@
-pub fn fibs(a: i64, b: i64) -> Fibs {
-    Fibs { a, b }
+use itertools::Itertools;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::CheckedAdd;
+use num_traits::One;
+use num_traits::Zero;
+
+use crate::factors;
+use crate::mul_mod;
+
+/// Iterates over the Fibonacci-like sequence a, b, a + b, ...
+///
+/// This works for any type that can be added, including BigInt and
+/// ModInt, which go on forever. For fixed size integers the sequence
+/// eventually overflows; by default the iterator stops after the last
+/// term that fits, but `on_overflow` can make that a panic instead.
+///
+/// ```
+/// use num_bigint::BigInt;
+///
+/// let evens: i64 = euler::fibs(1, 2).take_while(|&x| x < 100).filter(|x| x % 2 == 0).sum();
+/// assert_eq!(evens, 2 + 8 + 34);
+///
+/// // Problem 25: the first term with 1000 digits.
+/// let one = BigInt::from(1);
+/// let index = euler::fibs(one.clone(), one).position(|f| f.to_string().len() == 1000);
+/// assert_eq!(index, Some(4781));
+/// ```
+pub fn fibs<T: CheckedAdd + Clone>(a: T, b: T) -> Fibs<T> {
+    Fibs {
+        next: Some(a),
+        after: Some(b),
+        overflowed: false,
+        on_overflow: OnOverflow::Stop,
+    }
+}
+
+/// What a `Fibs` iterator does when the next term doesn't fit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OnOverflow {
+    /// End the sequence, as if it were finite.
+    Stop,
+    /// Panic, for when running out would be a bug.
+    Panic,
+}
+
+pub struct Fibs<T> {
+    next: Option<T>,
+    // None once this would have overflowed.
+    after: Option<T>,
+    overflowed: bool,
+    on_overflow: OnOverflow,
 }
 
-pub struct Fibs {
-    a: i64,
-    b: i64,
+impl<T> Fibs<T> {
+    /// Sets what to do when the sequence overflows.
+    ///
+    /// ```
+    /// use euler::OnOverflow;
+    ///
+    /// // The largest Fibonacci number that fits in a u8 is 233.
+    /// let last = euler::fibs(1_u8, 1).last();
+    /// assert_eq!(last, Some(233));
+    ///
+    /// let result = std::panic::catch_unwind(|| {
+    ///     euler::fibs(1_u8, 1).on_overflow(OnOverflow::Panic).count()
+    /// });
+    /// assert!(result.is_err());
+    /// ```
+    pub fn on_overflow(self, on_overflow: OnOverflow) -> Self {
+        Self {
+            on_overflow,
+            ..self
+        }
+    }
 }
 
-impl Iterator for Fibs {
-    type Item = i64;
+impl<T: CheckedAdd + Clone> Iterator for Fibs<T> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let c = self.a.checked_add(self.b)?;
-        let ans = self.a;
-        self.a = self.b;
-        self.b = c;
-        Some(ans)
+        let Some(current) = self.next.take() else {
+            if self.overflowed && self.on_overflow == OnOverflow::Panic {
+                panic!("Fibonacci sequence overflowed");
+            }
+            return None;
+        };
+        // Work out the term after next now, but only complain about an
+        // overflow once somebody asks for it.
+        if let Some(after) = self.after.take() {
+            self.after = current.checked_add(&after);
+            self.overflowed = self.after.is_none();
+            self.next = Some(after);
+        }
+        Some(current)
+    }
+}
+
+/// Calculates the nth Fibonacci number mod m, with F(0) = 0, F(1) = 1,
+/// in O(log n) steps, by fast doubling:
+///
+///   F(2k) = F(k) (2F(k + 1) - F(k))
+///   F(2k + 1) = F(k)² + F(k + 1)²
+///
+/// ```
+/// assert_eq!(euler::fib_mod(10, 1000), 55);
+/// assert_eq!(euler::fib_mod(1_000_000_000_000_000_000, 1_000_000_007), 209783453);
+/// ```
+pub fn fib_mod(n: u64, m: u64) -> u64 {
+    fib_pair_mod(n, m).0
+}
+
+// (F(n), F(n + 1)) mod m.
+fn fib_pair_mod(n: u64, m: u64) -> (u64, u64) {
+    if n == 0 {
+        return (0, 1 % m);
+    }
+    let (a, b) = fib_pair_mod(n / 2, m);
+    let twice_b_minus_a = ((2 * b as u128 + m as u128 - a as u128) % m as u128) as u64;
+    let c = mul_mod(a, twice_b_minus_a, m);
+    let d = ((mul_mod(a, a, m) as u128 + mul_mod(b, b, m) as u128) % m as u128) as u64;
+    if n.is_multiple_of(2) {
+        (c, d)
+    } else {
+        (d, ((c as u128 + d as u128) % m as u128) as u64)
+    }
+}
+
+/// Calculates the nth Fibonacci number exactly, by fast doubling. F(n)
+/// has about n / 5 digits, so this is only sensible for n up to
+/// millions.
+///
+/// ```
+/// assert_eq!(euler::fib(100).to_string(), "354224848179261915075");
+/// ```
+pub fn fib(n: u64) -> BigInt {
+    fib_pair(n).0
+}
+
+fn fib_pair(n: u64) -> (BigInt, BigInt) {
+    if n == 0 {
+        return (BigInt::zero(), BigInt::one());
+    }
+    let (a, b) = fib_pair(n / 2);
+    let c = &a * (&b * 2 - &a);
+    let d = &a * &a + &b * &b;
+    if n.is_multiple_of(2) {
+        (c, d)
+    } else {
+        let e = &c + &d;
+        (d, e)
+    }
+}
+
+/// Calculates the nth Lucas number L(n) = F(n - 1) + F(n + 1) mod m,
+/// with L(0) = 2, L(1) = 1.
+///
+/// ```
+/// assert_eq!(euler::lucas_mod(10, 1000), 123);
+/// ```
+pub fn lucas_mod(n: u64, m: u64) -> u64 {
+    let (a, b) = fib_pair_mod(n, m);
+    // L(n) = 2F(n + 1) - F(n)
+    ((2 * b as u128 + m as u128 - a as u128) % m as u128) as u64
+}
+
+/// Calculates the nth Lucas number exactly.
+pub fn lucas(n: u64) -> BigInt {
+    let (a, b) = fib_pair(n);
+    b * 2 - a
+}
+
+/// Finds the Pisano period π(m): the period of the Fibonacci numbers
+/// mod m.
+///
+/// For m = p^k × q^j × ..., π(m) is the lcm of the periods of the prime
+/// powers. For a prime p other than 2 and 5, π(p) divides p - 1 if p is
+/// ±1 mod 10, or 2(p + 1) otherwise, and π(p^k) divides p^(k-1) π(p).
+/// So each prime power starts with that multiple of its period, and
+/// divides out prime factors for as long as the sequence still repeats.
+///
+/// ```
+/// assert_eq!(euler::pisano_period(10), 60);
+/// assert_eq!(euler::pisano_period(1_000_000_007), 2_000_000_016);
+/// ```
+///
+/// Panics if m is 0.
+pub fn pisano_period(m: u64) -> u64 {
+    if m == 0 {
+        panic!("Pisano period mod 0");
+    }
+    let m = i64::try_from(m).expect("m is too big to factorize");
+    factors(m)
+        .dedup_with_count()
+        .map(|(k, p)| {
+            let p = p as u64;
+            let prime_power = p.pow(k as u32);
+            let period_of_p = match p {
+                2 => 3,
+                5 => 20,
+                _ if p % 10 == 1 || p % 10 == 9 => p - 1,
+                _ => 2 * (p + 1),
+            };
+            smallest_period(p.pow(k as u32 - 1) * period_of_p, prime_power)
+        })
+        .fold(1, |l, period| l.lcm(&period))
+}
+
+// Given some multiple of the Pisano period mod m, finds the period itself.
+fn smallest_period(multiple: u64, m: u64) -> u64 {
+    let repeats = |n: u64| fib_pair_mod(n, m) == (0, 1 % m);
+    let mut period = multiple;
+    for q in factors(multiple as i64).dedup() {
+        let q = q as u64;
+        while period.is_multiple_of(q) && repeats(period / q) {
+            period /= q;
+        }
+    }
+    period
+}
+
+/// Writes n as a sum of non-consecutive Fibonacci numbers, which can
+/// always be done in exactly one way. Returns them largest first.
+///
+/// ```
+/// assert_eq!(euler::zeckendorf(100), [89, 8, 3]);
+/// assert_eq!(euler::zeckendorf(0), []);
+/// ```
+pub fn zeckendorf(n: u64) -> Vec<u64> {
+    let fs = fibs(1_u64, 2).take_while(|&f| f <= n).collect::<Vec<_>>();
+    let mut rest = n;
+    let mut terms = vec![];
+    for &f in fs.iter().rev() {
+        if f <= rest {
+            terms.push(f);
+            rest -= f;
+        }
+    }
+    terms
+}
+
+/// Encodes the Zeckendorf representation of n as bits, where bit i
+/// stands for F(i + 2), so 1, 2, 3, 5, 8, ... No two neighbouring bits
+/// are set.
+///
+/// ```
+/// // 100 = 89 + 8 + 3 = F(11) + F(6) + F(4)
+/// assert_eq!(euler::zeckendorf_encode(100), 0b1000010100);
+/// assert_eq!(euler::zeckendorf_decode(0b1000010100), 100);
+/// ```
+pub fn zeckendorf_encode(n: u64) -> u128 {
+    let fs = fibs(1_u64, 2).collect::<Vec<_>>();
+    zeckendorf(n)
+        .into_iter()
+        .map(|f| 1 << fs.binary_search(&f).unwrap())
+        .sum()
+}
+
+/// Turns bits back into a number, treating bit i as F(i + 2). Unlike
+/// encoding, this doesn't mind neighbouring bits.
+///
+/// Panics if the result doesn't fit in a u64.
+pub fn zeckendorf_decode(bits: u128) -> u64 {
+    fibs(1_u64, 2)
+        .on_overflow(OnOverflow::Panic)
+        .take(128 - bits.leading_zeros() as usize)
+        .enumerate()
+        .filter(|(i, _)| bits & (1 << i) != 0)
+        .map(|(_, f)| f)
+        .fold(0, |sum, f| {
+            sum.checked_add(f).expect("Zeckendorf sum overflowed")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ModInt;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn test_last_representable_term() {
+        // F(92) is the largest Fibonacci number that fits in an i64.
+        assert_eq!(fibs(0_i64, 1).count(), 93);
+        assert_eq!(fibs(0_i64, 1).last(), Some(7_540_113_804_746_346_429));
+        assert_eq!(fibs(0_u64, 1).last(), Some(12_200_160_415_121_876_738));
+        assert_eq!(fibs(i8::MAX, 0).collect::<Vec<_>>(), [127, 0, 127, 127]);
+    }
+
+    #[test]
+    fn test_stop_is_fused() {
+        let mut fs = fibs(100_u8, 100);
+        assert_eq!(fs.next(), Some(100));
+        assert_eq!(fs.next(), Some(100));
+        assert_eq!(fs.next(), Some(200));
+        assert_eq!(fs.next(), None);
+        assert_eq!(fs.next(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_panic() {
+        fibs(0_i64, 1).on_overflow(OnOverflow::Panic).nth(93);
+    }
+
+    #[test]
+    fn test_panic_only_when_asked() {
+        let fs = fibs(0_i64, 1).on_overflow(OnOverflow::Panic);
+        assert_eq!(fs.take(93).count(), 93);
+    }
+
+    #[test]
+    fn test_other_types() {
+        let big = fibs(BigInt::from(0), BigInt::from(1)).nth(100).unwrap();
+        assert_eq!(big.to_string(), "354224848179261915075");
+
+        // The Pisano period mod 10 is 60.
+        type Mod10 = ModInt<10>;
+        let mod10 = fibs(Mod10::new(0), Mod10::new(1));
+        let digits = mod10.map(|f| f.value()).take(120).collect::<Vec<_>>();
+        assert_eq!(digits[..60], digits[60..]);
+        assert_eq!(digits[..8], [0, 1, 1, 2, 3, 5, 8, 3]);
+    }
+
+    #[test]
+    fn test_fib_mod() {
+        for m in 1..100 {
+            let expected = fibs(0_u64, 1).take(90).map(|f| f % m);
+            for (n, e) in expected.enumerate() {
+                assert_eq!(fib_mod(n as u64, m), e, "F({}) mod {}", n, m);
+            }
+        }
+        // Mod a number that's almost 2^64.
+        let m = u64::MAX - 58;
+        let expected = fibs(0_u64, 1).map(|f| f % m);
+        for (n, e) in expected.enumerate() {
+            assert_eq!(fib_mod(n as u64, m), e);
+        }
+    }
+
+    #[test]
+    fn test_exact() {
+        for (n, f) in fibs(BigInt::from(0), BigInt::from(1)).take(300).enumerate() {
+            assert_eq!(fib(n as u64), f);
+        }
+        let lucas_numbers = [2, 1, 3, 4, 7, 11, 18, 29, 47, 76];
+        for (n, l) in lucas_numbers.into_iter().enumerate() {
+            assert_eq!(lucas(n as u64), BigInt::from(l));
+            assert_eq!(lucas_mod(n as u64, 10), l % 10);
+        }
+        assert_eq!(fib(1000).to_string().len(), 209);
+    }
+
+    #[test]
+    fn test_pisano_brute_force() {
+        for m in 1..1000 {
+            let expected = (1..).find(|&n| fib_mod(n, m) == 0 && fib_mod(n + 1, m) == 1 % m);
+            assert_eq!(pisano_period(m), expected.unwrap(), "π({})", m);
+        }
+    }
+
+    #[test]
+    fn test_zeckendorf() {
+        for n in 0..10_000 {
+            let terms = zeckendorf(n);
+            assert_eq!(terms.iter().sum::<u64>(), n);
+            let bits = zeckendorf_encode(n);
+            assert_eq!(bits & (bits >> 1), 0, "{}", n);
+            assert_eq!(zeckendorf_decode(bits), n);
+        }
+        assert_eq!(zeckendorf_decode(zeckendorf_encode(u64::MAX)), u64::MAX);
     }
 }
@@ -0,0 +1,185 @@
+use crate::isqrt;
+
+/// The nth s-gonal number, ((s - 2)n² - (s - 4)n) / 2, which counts the
+/// dots in n nested s-sided polygons. s = 3 gives the triangular numbers,
+/// s = 4 the squares, s = 5 the pentagonal numbers, and so on.
+///
+/// ```
+/// assert_eq!(euler::polygonal(3, 10), 55);
+/// assert_eq!(euler::polygonal(5, 4), 22);
+/// assert_eq!(euler::polygonal(6, 143), 40755);
+/// ```
+///
+/// Panics if s < 3, or if the result doesn't fit in a u64.
+pub fn polygonal(s: u64, n: u64) -> u64 {
+    check_sides(s);
+    let (s, n) = (s as u128, n as u128);
+    (s - 2)
+        .checked_mul(n * n)
+        .map(|twice| (twice + 4 * n - s * n) / 2)
+        .and_then(|p| u64::try_from(p).ok())
+        .expect("Polygonal number is too big")
+}
+
+/// The nth triangular number, n(n + 1) / 2.
+///
+/// ```
+/// assert_eq!(euler::triangular(100), 5050);
+/// ```
+pub fn triangular(n: u64) -> u64 {
+    polygonal(3, n)
+}
+
+fn check_sides(s: u64) {
+    if s < 3 {
+        panic!("Polygons need at least 3 sides, not {}", s);
+    }
+}
+
+/// Finds the n with polygonal(s, n) = x, if x is an s-gonal number.
+///
+/// Solving the quadratic, n = ((s - 4) + √((s - 4)² + 8(s - 2)x)) / 2(s - 2),
+/// so x is s-gonal exactly when the square root is a whole number and
+/// the division comes out exactly. Everything is in integers, so there's
+/// no rounding to worry about.
+///
+/// ```
+/// // Problem 45: 40755 is triangular, pentagonal and hexagonal.
+/// assert_eq!(euler::polygonal_index(3, 40755), Some(285));
+/// assert_eq!(euler::polygonal_index(5, 40755), Some(165));
+/// assert_eq!(euler::polygonal_index(6, 40755), Some(143));
+/// assert_eq!(euler::polygonal_index(5, 40756), None);
+/// ```
+///
+/// Panics if s < 3.
+pub fn polygonal_index(s: u64, x: u64) -> Option<u64> {
+    let (root, exact) = discriminant_root(s, x);
+    // 0 comes from the other root of the quadratic.
+    if x == 0 {
+        return Some(0);
+    }
+    if !exact {
+        return None;
+    }
+    let (numerator, denominator) = (root + s as u128 - 4, 2 * (s as u128 - 2));
+    numerator
+        .is_multiple_of(denominator)
+        .then(|| (numerator / denominator) as u64)
+}
+
+/// The largest n with polygonal(s, n) ≤ x.
+///
+/// ```
+/// assert_eq!(euler::polygonal_root(3, 54), 9);
+/// assert_eq!(euler::polygonal_root(3, 55), 10);
+/// assert_eq!(euler::polygonal_root(4, 99), 9);
+/// ```
+///
+/// Panics if s < 3.
+pub fn polygonal_root(s: u64, x: u64) -> u64 {
+    let (root, _) = discriminant_root(s, x);
+    // Rounding the square root down can only round n down.
+    ((root + s as u128 - 4) / (2 * (s as u128 - 2))) as u64
+}
+
+// The integer square root of (s - 4)² + 8(s - 2)x, and whether it's exact.
+// Since the discriminant is at least 1, the root is too, so adding s - 4
+// to it never goes negative.
+fn discriminant_root(s: u64, x: u64) -> (u128, bool) {
+    check_sides(s);
+    let (s, x) = (s as u128, x as u128);
+    let discriminant = (s.abs_diff(4))
+        .checked_pow(2)
+        .zip((8 * (s - 2)).checked_mul(x))
+        .and_then(|(a, b)| a.checked_add(b))
+        .expect("Too many sides");
+    let root = isqrt(discriminant);
+    (root, root * root == discriminant)
+}
+
+/// Iterates over the s-gonal numbers polygonal(s, 1), polygonal(s, 2), ...
+/// stopping at the last one that fits in a u64.
+///
+/// ```
+/// let pentagonals = euler::polygonals(5).take(6).collect::<Vec<_>>();
+/// assert_eq!(pentagonals, [1, 5, 12, 22, 35, 51]);
+/// ```
+///
+/// Panics if s < 3.
+pub fn polygonals(s: u64) -> Polygonals {
+    check_sides(s);
+    Polygonals {
+        next: Some(1),
+        step: s - 1,
+        step_increase: s - 2,
+    }
+}
+
+pub struct Polygonals {
+    // None once the sequence has overflowed.
+    next: Option<u64>,
+    step: u64,
+    step_increase: u64,
+}
+
+impl Iterator for Polygonals {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let current = self.next?;
+        // Neighbouring s-gonal numbers differ by 1 + (s - 2)n.
+        self.next = current.checked_add(self.step);
+        self.step = self.step.saturating_add(self.step_increase);
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inverse() {
+        for s in 3..20 {
+            let mut expected = polygonals(s).enumerate().take(300).peekable();
+            for x in 0..polygonal(s, 300) {
+                let index = match expected.next_if(|&(_, p)| p == x) {
+                    Some((i, _)) => Some(i as u64 + 1),
+                    None => (x == 0).then_some(0),
+                };
+                assert_eq!(polygonal_index(s, x), index, "s={} x={}", s, x);
+                let root = polygonal_root(s, x);
+                assert!(polygonal(s, root) <= x && polygonal(s, root + 1) > x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_matches_formula() {
+        for s in 3..50 {
+            for (n, p) in polygonals(s).enumerate().take(100) {
+                assert_eq!(polygonal(s, n as u64 + 1), p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extremes() {
+        let n = polygonal_root(3, u64::MAX);
+        assert_eq!(polygonal_index(3, triangular(n)), Some(n));
+        assert!(triangular(n).checked_add(n + 1).is_none());
+        assert_eq!(polygonal_root(4, u64::MAX), u32::MAX as u64);
+        assert_eq!(
+            polygonal_index(4, u64::MAX - 2 * u32::MAX as u64),
+            Some(u32::MAX as u64)
+        );
+        let s = 1 << 40;
+        assert_eq!(polygonals(s).count(), polygonal_root(s, u64::MAX) as usize);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overflow() {
+        polygonal(3, u64::MAX);
+    }
+}
@@ -0,0 +1,522 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::Display;
+use std::ops::Add;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+
+use num_bigint::BigInt;
+use num_bigint::Sign;
+use num_integer::Integer;
+use num_traits::Signed;
+use num_traits::Zero;
+
+/// A fixed-point decimal number with a given number of digits after the
+/// point, stored as a BigInt scaled by a power of 10. It's for problems
+/// that want the first few hundred digits of something irrational, where
+/// f64 runs out after about 16.
+///
+/// Each number carries its own precision. Combining two numbers gives a
+/// result with the larger of their precisions, and every operation
+/// rounds its result to the nearest representable value. The functions
+/// that need a series, like `exp` and `ln`, work with some extra guard
+/// digits and round at the end, so their results are correctly rounded
+/// except in vanishingly rare near-ties.
+///
+/// ```
+/// use euler::Fixed;
+///
+/// let two = Fixed::from_int(2, 50);
+/// let root2 = two.sqrt();
+/// assert_eq!(root2.to_string(), "1.41421356237309504880168872420969807856967187537695");
+/// assert_eq!(format!("{:.5}", root2), "1.41421");
+///
+/// let e = Fixed::one(30).exp();
+/// assert_eq!(e.to_string(), "2.718281828459045235360287471353");
+/// assert_eq!(e.ln(), Fixed::one(30));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Fixed {
+    value: BigInt,
+    digits: u32,
+}
+
+fn pow10(digits: u32) -> BigInt {
+    BigInt::from(10).pow(digits)
+}
+
+// Divides, rounding to the nearest integer, with halves going away from
+// zero.
+fn div_round(a: &BigInt, b: &BigInt) -> BigInt {
+    let (q, r) = a.div_rem(b);
+    if r.abs() * 2 >= b.abs() {
+        if a.sign() == b.sign() {
+            q + 1
+        } else {
+            q - 1
+        }
+    } else {
+        q
+    }
+}
+
+// Changes the scale of a value from 10^from to 10^to, rounding if that
+// loses digits.
+fn rescale(value: &BigInt, from: u32, to: u32) -> BigInt {
+    match from.cmp(&to) {
+        Ordering::Less => value * pow10(to - from),
+        Ordering::Equal => value.clone(),
+        Ordering::Greater => div_round(value, &pow10(from - to)),
+    }
+}
+
+impl Fixed {
+    /// An integer, with the given number of digits after the point.
+    pub fn from_int<T: Into<BigInt>>(n: T, digits: u32) -> Self {
+        Self {
+            value: n.into() * pow10(digits),
+            digits,
+        }
+    }
+
+    /// The fraction p/q, rounded to the given number of digits.
+    ///
+    /// Panics if q is zero.
+    pub fn from_ratio<T: Into<BigInt>>(p: T, q: T, digits: u32) -> Self {
+        let value = div_round(&(p.into() * pow10(digits)), &q.into());
+        Self { value, digits }
+    }
+
+    pub fn zero(digits: u32) -> Self {
+        Self::from_int(0, digits)
+    }
+
+    pub fn one(digits: u32) -> Self {
+        Self::from_int(1, digits)
+    }
+
+    /// Reads a decimal like "-12.375", rounding it to the given number of
+    /// digits if it has more than that. Returns None if it isn't a valid
+    /// decimal.
+    pub fn parse(s: &str, digits: u32) -> Option<Self> {
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+        if int.is_empty() && frac.is_empty() {
+            return None;
+        }
+        let all_digits = |t: &str| t.bytes().all(|b| b.is_ascii_digit());
+        if !all_digits(int) || !all_digits(frac) {
+            return None;
+        }
+        let magnitude = BigInt::parse_bytes(format!("0{}{}", int, frac).as_bytes(), 10)?;
+        let value = rescale(&magnitude, frac.len() as u32, digits);
+        let value = if negative { -value } else { value };
+        Some(Self { value, digits })
+    }
+
+    /// The number of digits after the point.
+    pub fn precision(&self) -> u32 {
+        self.digits
+    }
+
+    /// The same number with a different number of digits after the
+    /// point, rounded if that's fewer than before.
+    pub fn with_precision(&self, digits: u32) -> Self {
+        Self {
+            value: rescale(&self.value, self.digits, digits),
+            digits,
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.value.is_negative()
+    }
+
+    pub fn abs(&self) -> Self {
+        Self {
+            value: self.value.abs(),
+            digits: self.digits,
+        }
+    }
+
+    /// The largest integer no bigger than this.
+    pub fn floor(&self) -> BigInt {
+        self.value.div_floor(&pow10(self.digits))
+    }
+
+    /// The part after the point, which is always in [0, 1).
+    pub fn fract(&self) -> Self {
+        Self {
+            value: self.value.mod_floor(&pow10(self.digits)),
+            digits: self.digits,
+        }
+    }
+
+    /// The nearest f64, or infinity if it's out of range.
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap()
+    }
+
+    /// The square root, to the same precision.
+    ///
+    /// Panics if the number is negative.
+    pub fn sqrt(&self) -> Self {
+        if self.is_negative() {
+            panic!("Square root of a negative number: {}", self);
+        }
+        // Take the root of the value scaled to 2(d + 2) digits, which
+        // gives the answer to d + 2 digits, then round.
+        const GUARD: u32 = 2;
+        let scaled = rescale(&self.value, self.digits, 2 * (self.digits + GUARD));
+        Self {
+            value: rescale(&scaled.sqrt(), self.digits + GUARD, self.digits),
+            digits: self.digits,
+        }
+    }
+
+    /// Raises e to this power, to the same precision.
+    ///
+    /// This halves x until it's tiny, sums the Taylor series, and then
+    /// squares the result back up again. Each squaring doubles the
+    /// relative error, so there are enough guard digits to cover that,
+    /// and the size of the answer.
+    ///
+    /// Panics if the result would be absurdly large.
+    pub fn exp(&self) -> Self {
+        let x = self.to_f64();
+        if x > 1e6 {
+            panic!("exp({}) is too big", self);
+        }
+        let halvings = x.abs().log2().max(0.0).ceil() as u32 + 8;
+        let int_digits = (x.max(0.0) * std::f64::consts::LOG10_E).ceil() as u32;
+        let guard = 10 + halvings + int_digits;
+        let w = self.digits + guard;
+        let one = pow10(w);
+
+        let r = div_round(
+            &rescale(&self.value, self.digits, w),
+            &(BigInt::from(1) << halvings),
+        );
+        let mut sum = one.clone();
+        let mut term = one.clone();
+        for n in 1_u32.. {
+            term = div_round(&(term * &r), &(&one * n));
+            if term.is_zero() {
+                break;
+            }
+            sum += &term;
+        }
+        for _ in 0..halvings {
+            sum = div_round(&(&sum * &sum), &one);
+        }
+        Self {
+            value: rescale(&sum, w, self.digits),
+            digits: self.digits,
+        }
+    }
+
+    /// The natural logarithm, to the same precision.
+    ///
+    /// This takes square roots until x is close to 1, so that
+    /// ln(x) = 2 atanh((x - 1) / (x + 1)) converges quickly, then
+    /// doubles the answer back up once for each root taken.
+    ///
+    /// Panics if the number isn't positive.
+    pub fn ln(&self) -> Self {
+        if self.value.sign() != Sign::Plus {
+            panic!("Logarithm of a non-positive number: {}", self);
+        }
+        // Aim for |x - 1| < 1/1000 after the roots.
+        let roots = (self.approx_ln().abs() * 1000.0).log2().max(0.0).ceil() as u32;
+        let guard = 10 + roots;
+        let w = self.digits + guard;
+        let one = pow10(w);
+
+        let mut x = rescale(&self.value, self.digits, w);
+        for _ in 0..roots {
+            x = (x * &one).sqrt();
+        }
+        let t = div_round(&((&x - &one) * &one), &(&x + &one));
+        let t2 = div_round(&(&t * &t), &one);
+        let mut sum = t.clone();
+        let mut power = t;
+        for n in 1_u32.. {
+            power = div_round(&(power * &t2), &one);
+            let term = &power / (2 * n + 1);
+            if term.is_zero() {
+                break;
+            }
+            sum += term;
+        }
+        Self {
+            value: rescale(&(sum << (roots + 1)), w, self.digits),
+            digits: self.digits,
+        }
+    }
+
+    // A rough natural log, good for any size of number.
+    fn approx_ln(&self) -> f64 {
+        let s = self.value.abs().to_string();
+        let lead = s.len().min(17);
+        let mantissa: f64 = s[..lead].parse().unwrap();
+        let exponent = s.len() as f64 - lead as f64 - self.digits as f64;
+        mantissa.ln() + exponent * std::f64::consts::LN_10
+    }
+
+    // The values of two numbers, scaled to the same precision.
+    fn aligned(&self, other: &Self) -> (BigInt, BigInt, u32) {
+        let digits = self.digits.max(other.digits);
+        let a = rescale(&self.value, self.digits, digits);
+        let b = rescale(&other.value, other.digits, digits);
+        (a, b, digits)
+    }
+}
+
+impl PartialEq for Fixed {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Fixed {}
+
+impl PartialOrd for Fixed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Fixed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (a, b, _) = self.aligned(other);
+        a.cmp(&b)
+    }
+}
+
+/// Shows all the digits after the point, or rounds to the precision in
+/// the format string, if there is one.
+impl Display for Fixed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = f.precision().map_or(self.digits, |p| p as u32);
+        let value = rescale(&self.value, self.digits, digits);
+        let sign = if value.is_negative() { "-" } else { "" };
+        let s = format!("{:0>width$}", value.abs(), width = digits as usize + 1);
+        let (int, frac) = s.split_at(s.len() - digits as usize);
+        if frac.is_empty() {
+            write!(f, "{}{}", sign, int)
+        } else {
+            write!(f, "{}{}.{}", sign, int, frac)
+        }
+    }
+}
+
+impl<'a> Add<&'a Fixed> for &'a Fixed {
+    type Output = Fixed;
+
+    fn add(self, rhs: Self) -> Fixed {
+        let (a, b, digits) = self.aligned(rhs);
+        Fixed {
+            value: a + b,
+            digits,
+        }
+    }
+}
+
+impl<'a> Sub<&'a Fixed> for &'a Fixed {
+    type Output = Fixed;
+
+    fn sub(self, rhs: Self) -> Fixed {
+        let (a, b, digits) = self.aligned(rhs);
+        Fixed {
+            value: a - b,
+            digits,
+        }
+    }
+}
+
+impl<'a> Mul<&'a Fixed> for &'a Fixed {
+    type Output = Fixed;
+
+    fn mul(self, rhs: Self) -> Fixed {
+        let digits = self.digits.max(rhs.digits);
+        let product = &self.value * &rhs.value;
+        Fixed {
+            value: rescale(&product, self.digits + rhs.digits, digits),
+            digits,
+        }
+    }
+}
+
+/// Panics on division by zero.
+impl<'a> Div<&'a Fixed> for &'a Fixed {
+    type Output = Fixed;
+
+    fn div(self, rhs: Self) -> Fixed {
+        if rhs.is_zero() {
+            panic!("Division by zero");
+        }
+        let digits = self.digits.max(rhs.digits);
+        let scaled = &self.value * pow10(digits + rhs.digits - self.digits);
+        Fixed {
+            value: div_round(&scaled, &rhs.value),
+            digits,
+        }
+    }
+}
+
+impl Neg for &Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed {
+            value: -&self.value,
+            digits: self.digits,
+        }
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        -&self
+    }
+}
+
+macro_rules! forward_binop {
+    ($trait:ident, $method:ident) => {
+        impl $trait for Fixed {
+            type Output = Fixed;
+
+            fn $method(self, rhs: Self) -> Fixed {
+                (&self).$method(&rhs)
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add);
+forward_binop!(Sub, sub);
+forward_binop!(Mul, mul);
+forward_binop!(Div, div);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn fixed(s: &str, digits: u32) -> Fixed {
+        Fixed::parse(s, digits).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(fixed("1.5", 3).to_string(), "1.500");
+        assert_eq!(fixed("-0.25", 2).to_string(), "-0.25");
+        assert_eq!(fixed("-0.25", 1).to_string(), "-0.3");
+        assert_eq!(fixed(".125", 2).to_string(), "0.13");
+        assert_eq!(fixed("7", 0).to_string(), "7");
+        assert_eq!(fixed("12.", 1).to_string(), "12.0");
+        assert_eq!(format!("{:.0}", fixed("2.5", 3)), "3");
+        assert_eq!(format!("{:.2}", fixed("-0.001", 3)), "0.00");
+        assert_eq!(Fixed::parse("", 3), None);
+        assert_eq!(Fixed::parse(".", 3), None);
+        assert_eq!(Fixed::parse("1.2.3", 3), None);
+        assert_eq!(Fixed::parse("1e5", 3), None);
+        assert_eq!(Fixed::parse("--1", 3), None);
+    }
+
+    #[test]
+    fn test_floor_and_fract() {
+        let x = fixed("-2.75", 2);
+        assert_eq!(x.floor(), BigInt::from(-3));
+        assert_eq!(x.fract(), fixed("0.25", 2));
+        assert_eq!(fixed("3", 5).floor(), BigInt::from(3));
+        assert!(fixed("3", 5).fract().is_zero());
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a = fixed("1.25", 2);
+        let b = fixed("0.125", 3);
+        assert_eq!((&a + &b).to_string(), "1.375");
+        assert_eq!((&b - &a).to_string(), "-1.125");
+        assert_eq!((&a * &b).to_string(), "0.156");
+        assert_eq!((&a / &b).to_string(), "10.000");
+        assert_eq!(Fixed::from_ratio(2, 3, 5).to_string(), "0.66667");
+        assert_eq!(Fixed::from_ratio(-2, 3, 5).to_string(), "-0.66667");
+        assert_eq!(
+            (Fixed::one(4) / Fixed::from_int(-3, 4)).to_string(),
+            "-0.3333"
+        );
+        assert!(a > b);
+        assert_eq!(fixed("0.5", 1), fixed("0.50", 2));
+    }
+
+    #[test]
+    fn test_sqrt() {
+        let digits = 1000;
+        let root = Fixed::from_int(2, digits).sqrt();
+        let square = &root * &root;
+        let three_ulps = Fixed::from_ratio(BigInt::from(3), pow10(digits), digits);
+        assert!((square - Fixed::from_int(2, digits)).abs() <= three_ulps);
+        assert_eq!(Fixed::from_int(144, 3).sqrt().to_string(), "12.000");
+        assert_eq!(fixed("0.0001", 4).sqrt().to_string(), "0.0100");
+    }
+
+    #[test]
+    fn test_exp_ln() {
+        // Problem 751's sort of precision, and more.
+        let ln2 = Fixed::from_int(2, 60).ln();
+        assert_eq!(
+            ln2.to_string(),
+            "0.693147180559945309417232121458176568075500134360255254120680"
+        );
+        let ln10 = Fixed::from_int(10, 40).ln();
+        assert_eq!(
+            ln10.to_string(),
+            "2.3025850929940456840179914546843642076011"
+        );
+        assert_eq!(
+            Fixed::from_int(-1, 20).exp().to_string(),
+            "0.36787944117144232160"
+        );
+        assert_eq!(Fixed::zero(10).exp(), Fixed::one(10));
+        assert_eq!(Fixed::one(10).ln(), Fixed::zero(10));
+
+        // A very large number and a very small one.
+        let big = Fixed::from_int(BigInt::from(10).pow(300), 30);
+        assert_eq!(big.ln().to_string(), "690.775527898213705205397436405309");
+        let tiny = Fixed::from_ratio(BigInt::from(1), pow10(30), 40);
+        assert_eq!(format!("{:.20}", tiny.ln()), "-69.07755278982137052054");
+    }
+
+    proptest! {
+        #[test]
+        fn exp_ln_round_trip(x in -5.0..50.0_f64) {
+            let x = Fixed::parse(&format!("{:.6}", x), 30).unwrap();
+            let y = x.exp().ln().with_precision(20);
+            prop_assert_eq!(y, x);
+        }
+
+        #[test]
+        fn matches_f64(a in -1e6..1e6_f64, b in 1e-3..1e6_f64) {
+            let fa = Fixed::parse(&format!("{:.10}", a), 20).unwrap();
+            let fb = Fixed::parse(&format!("{:.10}", b), 20).unwrap();
+            let close = |x: f64, y: f64| (x - y).abs() <= 1e-9 * (1.0 + y.abs());
+            prop_assert!(close((&fa * &fb).to_f64(), fa.to_f64() * fb.to_f64()));
+            prop_assert!(close((&fa / &fb).to_f64(), fa.to_f64() / fb.to_f64()));
+            prop_assert!(close(fb.ln().to_f64(), fb.to_f64().ln()));
+            prop_assert!(close(fb.sqrt().to_f64(), fb.to_f64().sqrt()));
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+use crate::crt;
+use crate::mod_inverse;
+use crate::mul_mod;
+use crate::pow_mod;
+use crate::totient;
+use crate::Fixed;
+
+/// The last k digits of base^exp, for k up to 19.
+///
+/// ```
+/// // Problem 48 is a sum of these.
+/// assert_eq!(euler::last_digits_pow(2, 100, 6), 205376);
+/// assert_eq!(euler::last_digits_pow(10, 3, 2), 0);
+/// ```
+pub fn last_digits_pow(base: u64, exp: u64, k: u32) -> u64 {
+    pow_mod(base, exp, ten_to(k))
+}
+
+fn ten_to(k: u32) -> u64 {
+    10_u64
+        .checked_pow(k)
+        .expect("Can only keep up to 19 digits")
+}
+
+/// The first k digits of base^exp, or all of them if there are fewer
+/// than k.
+///
+/// Writing base^exp = 10^(n + f), with n an integer and f in [0, 1),
+/// the leading digits are those of 10^(k - 1 + f). Finding f takes as
+/// many digits of log10(base) as there are in exp, plus k, so this uses
+/// `Fixed` with some to spare. The one thing that could go wrong is a
+/// power whose digits after the first k start with a run of twenty or
+/// so 9s, which would round up.
+///
+/// ```
+/// // 2^1000 = 10715086071862673209484250490600018105614048117055...
+/// assert_eq!(euler::leading_digits_pow(2, 1000, 10), 1071508607);
+/// assert_eq!(euler::leading_digits_pow(2, 10, 9), 1024);
+/// // Problem 686 is about powers of 2 starting with 123.
+/// assert_eq!(euler::leading_digits_pow(2, 90, 3), 123);
+/// ```
+///
+/// Panics unless k is in 1..=19.
+pub fn leading_digits_pow(base: u64, exp: u64, k: u32) -> u64 {
+    if k == 0 {
+        panic!("Need at least one leading digit");
+    }
+    let limit = ten_to(k);
+    if base <= 1 || exp == 0 {
+        return if exp == 0 { 1 } else { base };
+    }
+    // Small enough to work out in full?
+    if exp as f64 * (base as f64).log10() < k as f64 + 1.0 {
+        let mut n = BigInt::from(base).pow(exp as u32);
+        while n >= BigInt::from(limit) {
+            n /= 10;
+        }
+        return n.to_u64().unwrap();
+    }
+    // Powers of 10 would land right on a boundary.
+    let mut b = base;
+    while b.is_multiple_of(10) {
+        b /= 10;
+    }
+    if b == 1 {
+        return limit / 10;
+    }
+
+    let digits = k + 20 + exp.to_string().len() as u32;
+    let ln10 = Fixed::from_int(10, digits).ln();
+    let log = Fixed::from_int(base, digits).ln() / ln10.clone() * Fixed::from_int(exp, digits);
+    let f = log.fract() + Fixed::from_int(k - 1, digits);
+    (f * ln10).exp().floor().to_u64().unwrap()
+}
+
+/// Calculates the power tower a↑↑height = a^a^...^a mod m, with height
+/// copies of a. (a↑↑0 is 1.)
+///
+/// The tower is far too big to work out, but the exponents only matter
+/// mod φ(m), once they're big enough. The general form of Euler's
+/// theorem, which works even when a and m aren't coprime, is
+/// a^x ≡ a^(φ(m) + x mod φ(m)) (mod m) for x ≥ log₂(m). So the exponent
+/// tower is worked out mod φ(m), its exponent mod φ(φ(m)), and so on
+/// down until the modulus is 1.
+///
+/// ```
+/// // Problem 188: the last 8 digits of 1777↑↑1855.
+/// assert_eq!(euler::tower_mod(1777, 1855, 100_000_000), 95962097);
+/// assert_eq!(euler::tower_mod(3, 3, 1000), 7_625_597_484_987 % 1000);
+/// ```
+pub fn tower_mod(a: u64, height: u64, m: u64) -> u64 {
+    if m == 1 {
+        return 0;
+    }
+    if height == 0 {
+        return 1;
+    }
+    let phi = totient(m);
+    let exp = match small_tower(a, height - 1, phi) {
+        Some(e) => e,
+        None => tower_mod(a, height - 1, phi) + phi,
+    };
+    pow_mod(a, exp, m)
+}
+
+// Works out a↑↑height exactly, if it's less than limit.
+fn small_tower(a: u64, height: u64, limit: u64) -> Option<u64> {
+    match a {
+        // 0↑↑h alternates between 1 and 0.
+        0 => return Some((height + 1) % 2).filter(|&t| t < limit),
+        1 => return Some(1).filter(|&t| t < limit),
+        _ => (),
+    }
+    // Anything taller than this is more than 2^64.
+    if height > 5 {
+        return None;
+    }
+    let mut value = 1_u64;
+    for _ in 0..height {
+        value = u32::try_from(value).ok().and_then(|e| a.checked_pow(e))?;
+    }
+    Some(value).filter(|&v| v < limit)
+}
+
+/// The last k non-zero digits of n!, for k up to 10, which is the
+/// factorial with all its trailing zeros removed, mod 10^k.
+///
+/// The plan is to find n! mod 2^k and mod 5^k, then combine them. For
+/// either prime p, n! is p^e times a product of the numbers up to n
+/// with their factors of p removed. Those products repeat with period
+/// p^k, apart from the multiples of p, which give a smaller factorial.
+/// So with a table of one period, this takes O(p^k + log n) steps, and
+/// the table is what limits k: 5^10 entries is already 78MB.
+///
+/// ```
+/// // Problem 160: the last 5 non-zero digits of 10^12 factorial.
+/// assert_eq!(euler::factorial_last_nonzero_digits(1_000_000_000_000, 5), 16576);
+/// // 10! = 3628800.
+/// assert_eq!(euler::factorial_last_nonzero_digits(10, 3), 288);
+/// ```
+///
+/// Panics if k is more than 10.
+pub fn factorial_last_nonzero_digits(n: u64, k: u32) -> u64 {
+    if k > 10 {
+        panic!("Can only find up to 10 non-zero digits, not {}", k);
+    }
+    let (p2, p5) = (2_u64.pow(k), 5_u64.pow(k));
+    let (odd2, e2) = factorial_without_prime(n, 2, p2);
+    let (odd5, e5) = factorial_without_prime(n, 5, p5);
+    // Dividing out 10^e5 leaves 2^(e2 - e5) behind.
+    let mod5 = mul_mod(odd5, pow_mod(mod_inverse(2, p5).unwrap(), e5, p5), p5);
+    let mod2 = mul_mod(
+        mul_mod(odd2, pow_mod(2, e2 - e5, p2), p2),
+        pow_mod(mod_inverse(5, p2).unwrap(), e5, p2),
+        p2,
+    );
+    crt(&[(mod2, p2), (mod5, p5)]).unwrap().0
+}
+
+// The biggest prime power factorial_without_prime will make a table for,
+// which is just over 5^10.
+const MAX_FACTORIAL_PERIOD: u64 = 10_000_000;
+
+// Splits n! into p^e × r, and returns r mod q (a power of p) and e.
+// Panics if q is more than MAX_FACTORIAL_PERIOD.
+pub(crate) fn factorial_without_prime(n: u64, p: u64, q: u64) -> (u64, u64) {
+    if q > MAX_FACTORIAL_PERIOD {
+        panic!("Prime power {} is too big to tabulate factorials for", q);
+    }
+    // products[i] is the product of the numbers in 1..=i that aren't
+    // multiples of p, mod q.
+    let mut products = vec![1 % q; q as usize + 1];
+    for i in 1..=q {
+        let factor = if i.is_multiple_of(p) { 1 } else { i };
+        products[i as usize] = mul_mod(products[i as usize - 1], factor, q);
+    }
+    let mut result = 1 % q;
+    let mut exponent = 0;
+    let mut n = n;
+    while n > 0 {
+        let full_periods = pow_mod(products[q as usize], n / q, q);
+        result = mul_mod(
+            result,
+            mul_mod(full_periods, products[(n % q) as usize], q),
+            q,
+        );
+        // The multiples of p contribute p^(n/p) × (n/p)!.
+        n /= p;
+        exponent += n;
+    }
+    (result, exponent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::One;
+    use num_traits::Zero;
+
+    #[test]
+    fn test_leading_digits() {
+        for base in [2_u64, 3, 7, 10, 12, 99, 1000, 123456789] {
+            for exp in [1_u64, 2, 5, 17, 100, 333, 1000] {
+                let s = BigInt::from(base).pow(exp as u32).to_string();
+                for k in [1, 3, 10, 15] {
+                    let expected: u64 = s[..s.len().min(k)].parse().unwrap();
+                    assert_eq!(
+                        leading_digits_pow(base, exp, k as u32),
+                        expected,
+                        "{}^{}",
+                        base,
+                        exp
+                    );
+                }
+            }
+        }
+        assert_eq!(leading_digits_pow(0, 0, 3), 1);
+        assert_eq!(leading_digits_pow(0, 5, 3), 0);
+        assert_eq!(leading_digits_pow(1, 500, 3), 1);
+    }
+
+    #[test]
+    fn test_leading_digits_huge_exponent() {
+        // 2^(10^15) starts 1567.5223...
+        assert_eq!(leading_digits_pow(2, 1_000_000_000_000_000, 4), 1567);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_no_leading_digits() {
+        leading_digits_pow(2, 1000, 0);
+    }
+
+    #[test]
+    fn test_tower_brute_force() {
+        for a in 0..6_u64 {
+            for height in 0..4 {
+                let exact = (0..height).fold(BigInt::one(), |t, _| {
+                    BigInt::from(a).pow(t.to_u32().unwrap())
+                });
+                for m in 1..50 {
+                    let expected = (&exact % BigInt::from(m)).to_u64().unwrap();
+                    assert_eq!(
+                        tower_mod(a, height, m),
+                        expected,
+                        "{}↑↑{} mod {}",
+                        a,
+                        height,
+                        m
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_factorial_brute_force() {
+        let mut factorial = BigInt::one();
+        for n in 0..300_u64 {
+            if n > 0 {
+                factorial *= n;
+            }
+            let mut stripped = factorial.clone();
+            while (&stripped % 10u32).is_zero() {
+                stripped /= 10;
+            }
+            for k in 1..=6 {
+                let expected = (&stripped % 10_u64.pow(k)).to_u64().unwrap();
+                assert_eq!(
+                    factorial_last_nonzero_digits(n, k),
+                    expected,
+                    "{}! k={}",
+                    n,
+                    k
+                );
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_factorial_too_many_digits() {
+        factorial_last_nonzero_digits(100, 11);
+    }
+}
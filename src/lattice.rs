@@ -0,0 +1,240 @@
+use std::collections::HashSet;
+
+use num_bigint::BigInt;
+use num_traits::One;
+use num_traits::Zero;
+
+use crate::binomial_big;
+use crate::binomial_mod;
+use crate::Factorials;
+
+/// Counts the paths from `from` to `to` that only step right or up, one
+/// unit at a time, and never touch a blocked point.
+///
+/// Without obstacles, a path that goes dx right and dy up is a choice of
+/// which dx of its dx + dy steps go right, so there are C(dx + dy, dx)
+/// of them. The obstacles come off by inclusion–exclusion: sorting them,
+/// the paths whose first obstacle is o are the paths to o, minus those
+/// that hit an earlier obstacle first. That takes O(k²) binomials for k
+/// obstacles, however big the grid is.
+///
+/// ```
+/// // Problem 15, but with the middle of the grid blocked off.
+/// assert_eq!(euler::lattice_paths((0, 0), (20, 20), &[]), 137846528820_u64.into());
+/// assert_eq!(euler::lattice_paths((0, 0), (20, 20), &[(10, 10)]), 103_711_749_284_u64.into());
+/// ```
+pub fn lattice_paths(from: (u64, u64), to: (u64, u64), blocked: &[(u64, u64)]) -> BigInt {
+    let free_paths =
+        |a: (u64, u64), b: (u64, u64)| match (b.0.checked_sub(a.0), b.1.checked_sub(a.1)) {
+            (Some(dx), Some(dy)) => binomial_big(dx + dy, dx),
+            _ => BigInt::zero(),
+        };
+    avoiding(from, to, blocked, free_paths, |total, a, b| total - a * b)
+}
+
+/// `lattice_paths` mod m.
+///
+/// ```
+/// let paths = euler::lattice_paths_mod((0, 0), (100_000, 100_000), &[(3, 4)], 1_000_000_007);
+/// assert_eq!(paths, 249_917_472);
+/// ```
+///
+/// This is quickest when m is a prime bigger than the length of a path,
+/// since then the binomials come from a table of factorials.
+pub fn lattice_paths_mod(from: (u64, u64), to: (u64, u64), blocked: &[(u64, u64)], m: u64) -> u64 {
+    let steps = (to.0 + to.1).saturating_sub(from.0 + from.1);
+    let mut factorials = (steps < m && primal::is_prime(m)).then(|| Factorials::new(m));
+    let free_paths =
+        |a: (u64, u64), b: (u64, u64)| match (b.0.checked_sub(a.0), b.1.checked_sub(a.1)) {
+            (Some(dx), Some(dy)) => match factorials.as_mut() {
+                Some(f) => f.binomial(dx + dy, dx),
+                None => binomial_mod(dx + dy, dx, m),
+            },
+            _ => 0,
+        };
+    let m = m as u128;
+    avoiding(from, to, blocked, free_paths, |total, &a, &b| {
+        ((total as u128 + m - (a as u128 * b as u128) % m) % m) as u64
+    })
+}
+
+// The inclusion–exclusion for lattice_paths, given a way of counting
+// paths with no obstacles, and of working out total - a × b.
+fn avoiding<T>(
+    from: (u64, u64),
+    to: (u64, u64),
+    blocked: &[(u64, u64)],
+    mut free_paths: impl FnMut((u64, u64), (u64, u64)) -> T,
+    subtract_product: impl Fn(T, &T, &T) -> T,
+) -> T {
+    // Only obstacles inside the rectangle matter. Sorting them means a
+    // path can only go through them in order; the target goes last.
+    let inside = |p: &(u64, u64)| from.0 <= p.0 && p.0 <= to.0 && from.1 <= p.1 && p.1 <= to.1;
+    let mut points = blocked.iter().copied().filter(inside).collect::<Vec<_>>();
+    points.sort_unstable();
+    points.dedup();
+    points.push(to);
+
+    // first_hits[i] counts the paths to points[i] that avoid all the
+    // obstacles before it.
+    let mut first_hits: Vec<T> = Vec::with_capacity(points.len());
+    for (i, &p) in points.iter().enumerate() {
+        let mut total = free_paths(from, p);
+        for (j, &q) in points[..i].iter().enumerate() {
+            if q.1 <= p.1 {
+                total = subtract_product(total, &first_hits[j], &free_paths(q, p));
+            }
+        }
+        first_hits.push(total);
+    }
+    first_hits.pop().unwrap()
+}
+
+/// Counts the paths from (0, 0) to `to` made of the given steps, which
+/// can't go left or down, that never touch a blocked point.
+///
+/// This works cell by cell through the grid, adding up the ways into
+/// each one, so it takes O(width × height × steps) time.
+///
+/// ```
+/// // Delannoy numbers allow diagonal steps as well.
+/// let steps = [(1, 0), (0, 1), (1, 1)];
+/// assert_eq!(euler::step_paths((3, 3), &steps, &[]), 63.into());
+/// // A knight that only moves right and up.
+/// assert_eq!(euler::step_paths((6, 6), &[(1, 2), (2, 1)], &[(3, 3)]), 2.into());
+/// ```
+///
+/// Panics if a step is (0, 0).
+pub fn step_paths(to: (u64, u64), steps: &[(u64, u64)], blocked: &[(u64, u64)]) -> BigInt {
+    grid_dp(to, steps, blocked, BigInt::one())
+}
+
+/// `step_paths` mod m.
+pub fn step_paths_mod(to: (u64, u64), steps: &[(u64, u64)], blocked: &[(u64, u64)], m: u64) -> u64 {
+    grid_dp(to, steps, blocked, Modulo(1 % m, m)).0
+}
+
+// What grid_dp needs from its counts: adding, and a zero to start from.
+trait PathCount: Clone + for<'a> std::ops::AddAssign<&'a Self> {
+    fn zero_like(&self) -> Self;
+}
+
+// A number mod m, which has to carry m around with it.
+#[derive(Clone)]
+struct Modulo(u64, u64);
+
+impl std::ops::AddAssign<&Modulo> for Modulo {
+    fn add_assign(&mut self, other: &Modulo) {
+        self.0 = ((self.0 as u128 + other.0 as u128) % self.1 as u128) as u64;
+    }
+}
+
+impl PathCount for Modulo {
+    fn zero_like(&self) -> Self {
+        Modulo(0, self.1)
+    }
+}
+
+impl PathCount for BigInt {
+    fn zero_like(&self) -> Self {
+        BigInt::zero()
+    }
+}
+
+fn grid_dp<T: PathCount>(
+    to: (u64, u64),
+    steps: &[(u64, u64)],
+    blocked: &[(u64, u64)],
+    one: T,
+) -> T {
+    if steps.contains(&(0, 0)) {
+        panic!("Paths can't take (0, 0) steps");
+    }
+    let (width, height) = (to.0 as usize + 1, to.1 as usize + 1);
+    let blocked = blocked.iter().copied().collect::<HashSet<_>>();
+    let mut ways = vec![one.zero_like(); width * height];
+    for y in 0..height {
+        for x in 0..width {
+            if blocked.contains(&(x as u64, y as u64)) {
+                continue;
+            }
+            let mut total = if (x, y) == (0, 0) {
+                one.clone()
+            } else {
+                one.zero_like()
+            };
+            for &(dx, dy) in steps {
+                let (dx, dy) = (dx as usize, dy as usize);
+                if dx <= x && dy <= y {
+                    total += &ways[(y - dy) * width + x - dx];
+                }
+            }
+            ways[y * width + x] = total;
+        }
+    }
+    ways.pop().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::ToPrimitive;
+
+    const RIGHT_UP: [(u64, u64); 2] = [(1, 0), (0, 1)];
+
+    #[test]
+    fn test_matches_dp() {
+        // Some fixed but scattered obstacles.
+        let obstacles = (0..40_u64)
+            .map(|i| (i * 7 % 13, i * 11 % 17))
+            .collect::<Vec<_>>();
+        for count in [0, 1, 5, 20, 40] {
+            let blocked = &obstacles[..count];
+            for to in [(0, 0), (3, 5), (12, 16), (20, 20)] {
+                let expected = step_paths(to, &RIGHT_UP, blocked);
+                assert_eq!(lattice_paths((0, 0), to, blocked), expected);
+                for m in [1_u64, 2, 1000, 1_000_000_007] {
+                    let expected = (&expected % m).to_u64().unwrap();
+                    assert_eq!(lattice_paths_mod((0, 0), to, blocked, m), expected);
+                    assert_eq!(step_paths_mod(to, &RIGHT_UP, blocked, m), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_edges() {
+        // Blocking either end, or going backwards, leaves no paths.
+        assert_eq!(lattice_paths((0, 0), (5, 5), &[(0, 0)]), BigInt::zero());
+        assert_eq!(lattice_paths((0, 0), (5, 5), &[(5, 5)]), BigInt::zero());
+        assert_eq!(lattice_paths((3, 3), (5, 2), &[]), BigInt::zero());
+        assert_eq!(lattice_paths((2, 2), (2, 2), &[]), BigInt::one());
+        // Obstacles outside the rectangle and repeats don't matter.
+        let outside = [(9, 9), (0, 4), (9, 9), (5, 1)];
+        assert_eq!(
+            lattice_paths((1, 0), (4, 3), &outside),
+            lattice_paths((0, 0), (3, 3), &[])
+        );
+        assert_eq!(
+            lattice_paths((0, 0), (3, 3), &[(1, 1), (1, 1)]),
+            lattice_paths((0, 0), (3, 3), &[(1, 1)])
+        );
+        assert_eq!(step_paths((3, 3), &RIGHT_UP, &[(0, 0)]), BigInt::zero());
+    }
+
+    #[test]
+    fn test_shifted_start() {
+        let blocked = [(4, 6), (5, 5), (7, 9)];
+        let shifted = blocked.map(|(x, y)| (x - 2, y - 3));
+        assert_eq!(
+            lattice_paths((2, 3), (12, 14), &blocked),
+            step_paths((10, 11), &RIGHT_UP, &shifted)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_standing_still() {
+        step_paths((3, 3), &[(0, 0), (1, 1)], &[]);
+    }
+}
@@ -1,31 +1,221 @@
 mod best;
+mod combinatorics;
+mod constants;
+mod continued_fractions;
 mod decimal;
+mod digit_dp;
+mod digits;
+mod expressions;
 mod factors;
 mod fibs;
+mod figurate;
+mod fixed;
+mod huge;
+mod lattice;
+mod modular;
+mod orbit;
+mod palindromes;
+mod pandigital;
 mod partitions;
+mod pattern;
+mod pell;
+mod permutations;
+mod power_compare;
+mod rational;
+mod recurrence;
+mod residues;
 mod runner;
 
 pub use best::Best;
 pub use best::CountOf;
 
+pub use combinatorics::bell;
+pub use combinatorics::binomial;
+pub use combinatorics::binomial_big;
+pub use combinatorics::binomial_mod;
+pub use combinatorics::binomial_mod_prime_power;
+pub use combinatorics::catalan;
+pub use combinatorics::derangements;
+pub use combinatorics::lucas_binomial;
+pub use combinatorics::multinomial;
+pub use combinatorics::stirling1;
+pub use combinatorics::stirling2;
+pub use combinatorics::Factorials;
+
+pub use constants::cf_digits;
+pub use constants::e_digits;
+pub use constants::phi_digits;
+pub use constants::pi_digits;
+pub use constants::sqrt_digits;
+pub use constants::CfDigits;
+pub use constants::PiDigits;
+
+pub use continued_fractions::best_approximation;
+pub use continued_fractions::convergents;
+pub use continued_fractions::farey;
+pub use continued_fractions::quadratic_cf;
+pub use continued_fractions::rational_cf;
+pub use continued_fractions::sqrt_cf;
+pub use continued_fractions::stern_brocot_fraction;
+pub use continued_fractions::stern_brocot_path;
+pub use continued_fractions::Convergents;
+pub use continued_fractions::Direction;
+pub use continued_fractions::Farey;
+pub use continued_fractions::PeriodicCf;
+
+pub use decimal::cycle_length;
+pub use decimal::expand_fraction;
+pub use decimal::fraction_digits;
 pub use decimal::is_palindrome;
+pub use decimal::Expansion;
 pub use decimal::POWERS_OF_10;
 
+pub use digit_dp::count_up_to;
+pub use digit_dp::sum_up_to;
+pub use digit_dp::DigitDp;
+
+pub use digits::digit_product;
+pub use digits::digit_sum;
+pub use digits::digits;
+pub use digits::digits_msb;
+pub use digits::from_digits;
+pub use digits::num_digits;
+pub use digits::reverse_digits;
+pub use digits::rotate_digits;
+pub use digits::Digits;
+
+pub use expressions::Expr;
+pub use expressions::ExprSearch;
+pub use expressions::Op;
+pub use expressions::Reachable;
+
 pub use factors::factors;
 
+pub use fixed::Fixed;
+
+pub use fibs::fib;
+pub use fibs::fib_mod;
 pub use fibs::fibs;
+pub use fibs::lucas;
+pub use fibs::lucas_mod;
+pub use fibs::pisano_period;
+pub use fibs::zeckendorf;
+pub use fibs::zeckendorf_decode;
+pub use fibs::zeckendorf_encode;
 pub use fibs::Fibs;
+pub use fibs::OnOverflow;
+
+pub use figurate::polygonal;
+pub use figurate::polygonal_index;
+pub use figurate::polygonal_root;
+pub use figurate::polygonals;
+pub use figurate::triangular;
+pub use figurate::Polygonals;
+
+pub use huge::factorial_last_nonzero_digits;
+pub use huge::last_digits_pow;
+pub use huge::leading_digits_pow;
+pub use huge::tower_mod;
+
+pub use lattice::lattice_paths;
+pub use lattice::lattice_paths_mod;
+pub use lattice::step_paths;
+pub use lattice::step_paths_mod;
+
+pub use modular::crt;
+pub use modular::mod_inverse;
+pub use modular::mul_mod;
+pub use modular::multiplicative_order;
+pub use modular::pow_mod;
+pub use modular::totient;
+pub use modular::ModInt;
+
+pub use orbit::find_cycle;
+pub use orbit::Orbit;
+pub use orbit::OrbitCache;
 
-pub use crate::partitions::partitions3;
+pub use palindromes::double_palindromes;
+pub use palindromes::is_palindrome_in_base;
+pub use palindromes::palindromes;
+pub use palindromes::Palindromes;
+
+pub use pandigital::is_digit_permutation;
+pub use pandigital::is_pandigital;
+pub use pandigital::DigitSignature;
+
+pub use partitions::coin_partition_count;
+pub use partitions::distinct_partition_count;
+pub use partitions::odd_partition_count;
+pub use partitions::partition_count;
+pub use partitions::partition_count_at_most_k;
+pub use partitions::partition_counts_mod;
+pub use partitions::partitions;
+pub use partitions::partitions3;
+pub use partitions::partitions_k;
+pub use partitions::Partitions;
+pub use partitions::PartitionsK;
+pub use partitions::PartitionsMod;
+
+pub use pattern::DigitPattern;
+
+pub use pell::negative_pell_fundamental;
+pub use pell::pell_fundamental;
+pub use pell::pell_fundamental_solutions;
+pub use pell::pell_solutions;
+pub use pell::PellSolutions;
+
+pub use permutations::combination_rank;
+pub use permutations::combination_unrank;
+pub use permutations::multiset_permutations;
+pub use permutations::next_permutation;
+pub use permutations::permutation_rank;
+pub use permutations::permutation_unrank;
+pub use permutations::prev_permutation;
+pub use permutations::MultisetPermutations;
+
+pub use power_compare::compare_powers;
+pub use power_compare::Power;
+
+pub use rational::Rational;
+
+pub use recurrence::berlekamp_massey;
+pub use recurrence::extrapolate;
+pub use recurrence::Recurrence;
+
+pub use residues::jacobi;
+pub use residues::kronecker;
+pub use residues::legendre;
+pub use residues::quadratic_residues;
 
 pub use runner::check_all_solutions;
 pub use runner::Checkable;
 pub use runner::TestRunner;
 
-use integer_sqrt::IntegerSquareRoot;
+use num_integer::Roots;
+
+/// The integer square root: the largest r with r² ≤ t. Works for any
+/// integer type, including BigInt.
+///
+/// Panics if t is negative.
+pub fn isqrt<T: Roots>(t: T) -> T {
+    t.sqrt()
+}
 
-pub fn isqrt<T: IntegerSquareRoot>(t: T) -> T {
-    t.integer_sqrt()
+/// The integer kth root: the largest r with r^k ≤ t, for t ≥ 0. Odd
+/// roots of negative numbers round towards zero.
+///
+/// ```
+/// use num_bigint::BigInt;
+///
+/// assert_eq!(euler::iroot(1_000_000_u64, 3), 100);
+/// assert_eq!(euler::iroot(999_999_u64, 3), 99);
+/// let big = BigInt::from(3).pow(100);
+/// assert_eq!(euler::iroot(big, 20), BigInt::from(243));
+/// ```
+///
+/// Panics if k is 0, or k is even and t is negative.
+pub fn iroot<T: Roots>(t: T, k: u32) -> T {
+    t.nth_root(k)
 }
 
 use linkme::distributed_slice;
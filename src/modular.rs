@@ -0,0 +1,315 @@
+use std::ops::Add;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+
+use itertools::Itertools;
+use num_integer::Integer;
+use num_traits::CheckedAdd;
+use num_traits::One;
+use num_traits::Zero;
+
+use crate::factors;
+
+/// Calculates a × b mod m without overflowing, by going through u128.
+pub fn mul_mod(a: u64, b: u64, m: u64) -> u64 {
+    ((a as u128 * b as u128) % m as u128) as u64
+}
+
+/// Calculates base^exp mod m by repeated squaring.
+///
+/// ```
+/// assert_eq!(euler::pow_mod(2, 10, 1000), 24);
+/// assert_eq!(euler::pow_mod(3, 0, 7), 1);
+/// assert_eq!(euler::pow_mod(5, 3, 1), 0);
+/// ```
+pub fn pow_mod(base: u64, mut exp: u64, m: u64) -> u64 {
+    let mut result = 1 % m;
+    let mut b = base % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, b, m);
+        }
+        b = mul_mod(b, b, m);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Finds the inverse of a mod m: the x in 0..m with ax ≡ 1 (mod m), if
+/// there is one, which is when a and m are coprime.
+///
+/// ```
+/// assert_eq!(euler::mod_inverse(3, 10), Some(7));
+/// assert_eq!(euler::mod_inverse(4, 10), None);
+/// ```
+pub fn mod_inverse(a: u64, m: u64) -> Option<u64> {
+    let e = i128::from(a % m).extended_gcd(&i128::from(m));
+    if e.gcd != 1 {
+        return None;
+    }
+    Some(e.x.rem_euclid(i128::from(m)) as u64)
+}
+
+/// Solves x ≡ r (mod m) for each (r, m) pair with the Chinese remainder
+/// theorem, returning x mod the lcm of the moduli. Returns None if the
+/// congruences contradict each other.
+///
+/// ```
+/// assert_eq!(euler::crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(euler::crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(euler::crt(&[(1, 4), (2, 6)]), None);
+/// ```
+///
+/// Panics if the lcm doesn't fit in a u64.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let mut x = 0_u64;
+    let mut modulus = 1_u64;
+    for &(r, m) in congruences {
+        let r = r % m;
+        // Solve x + modulus × t ≡ r (mod m) for t.
+        let g = modulus.gcd(&m);
+        let diff = (r as i128 - x as i128).rem_euclid(m as i128) as u64;
+        if !diff.is_multiple_of(g) {
+            return None;
+        }
+        let m_g = m / g;
+        let inv = mod_inverse(modulus / g, m_g).unwrap();
+        let t = mul_mod(diff / g, inv, m_g);
+        let lcm = modulus.checked_mul(m_g).expect("lcm of moduli is too big");
+        x = ((x as u128 + modulus as u128 * t as u128) % lcm as u128) as u64;
+        modulus = lcm;
+    }
+    Some((x, modulus))
+}
+
+/// Euler's totient function: how many numbers in 1..=n are coprime to n.
+///
+/// Panics if n is 0 or too big to factorize as an i64.
+pub fn totient(n: u64) -> u64 {
+    let n = i64::try_from(n).expect("n is too big to factorize");
+    factors(n)
+        .dedup_with_count()
+        .map(|(k, p)| (p - 1) as u64 * (p as u64).pow(k as u32 - 1))
+        .product()
+}
+
+/// Finds the multiplicative order of a mod m: the smallest k > 0 with
+/// a^k ≡ 1 (mod m). There isn't one unless a and m are coprime, in
+/// which case this returns None.
+///
+/// The order divides the totient φ(m), so rather than stepping through
+/// the powers of a, this starts from φ(m) and divides out each prime
+/// factor for as long as the power stays 1.
+///
+/// ```
+/// // 1/7 = 0.(142857) repeats every 6 digits.
+/// assert_eq!(euler::multiplicative_order(10, 7), Some(6));
+/// assert_eq!(euler::multiplicative_order(2, 7), Some(3));
+/// assert_eq!(euler::multiplicative_order(10, 12), None);
+/// ```
+pub fn multiplicative_order(a: u64, m: u64) -> Option<u64> {
+    if m == 0 || a.gcd(&m) != 1 {
+        return None;
+    }
+    if m == 1 {
+        return Some(1);
+    }
+    let phi = totient(m);
+    let mut order = phi;
+    for p in factors(phi as i64).dedup() {
+        let p = p as u64;
+        while order.is_multiple_of(p) && pow_mod(a, order / p, m) == 1 {
+            order /= p;
+        }
+    }
+    Some(order)
+}
+
+/// An integer mod M, where M is fixed at compile time. Arithmetic wraps
+/// around mod M, so it never overflows, which makes this handy for
+/// running generic code like `fibs` mod some modulus.
+///
+/// ```
+/// use euler::ModInt;
+///
+/// type Mod7 = ModInt<7>;
+/// let x = Mod7::new(5);
+/// assert_eq!((x + x).value(), 3);
+/// assert_eq!((x * x).value(), 4);
+/// assert_eq!((-x).value(), 2);
+/// assert_eq!(x.pow(6), Mod7::new(1));
+/// assert_eq!(x.inverse(), Some(Mod7::new(3)));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ModInt<const M: u64>(u64);
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(n: u64) -> Self {
+        Self(n % M)
+    }
+
+    /// The value, in 0..M.
+    pub fn value(self) -> u64 {
+        self.0
+    }
+
+    pub fn pow(self, exp: u64) -> Self {
+        Self(pow_mod(self.0, exp, M))
+    }
+
+    /// The multiplicative inverse, if the value is coprime to M.
+    pub fn inverse(self) -> Option<Self> {
+        mod_inverse(self.0, M).map(Self)
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(n: u64) -> Self {
+        Self::new(n)
+    }
+}
+
+impl<const M: u64> std::fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(((self.0 as u128 + rhs.0 as u128) % M as u128) as u64)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + (-rhs)
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(mul_mod(self.0, rhs.0, M))
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self((M - self.0) % M)
+    }
+}
+
+impl<const M: u64> Zero for ModInt<M> {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const M: u64> One for ModInt<M> {
+    fn one() -> Self {
+        Self::new(1)
+    }
+}
+
+/// Arithmetic mod M can't overflow, so this always succeeds.
+impl<const M: u64> CheckedAdd for ModInt<M> {
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(*self + *rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_totient() {
+        let expected = [1, 1, 2, 2, 4, 2, 6, 4, 6, 4, 10, 4];
+        for (n, phi) in (1..).zip(expected) {
+            assert_eq!(totient(n), phi, "φ({})", n);
+        }
+        assert_eq!(totient(1 << 40), 1 << 39);
+    }
+
+    #[test]
+    fn test_order_brute_force() {
+        for m in 1..200_u64 {
+            for a in 0..m + 5 {
+                let expected = if a.gcd(&m) == 1 {
+                    (1..).find(|&k| pow_mod(a, k, m) == 1 % m)
+                } else {
+                    None
+                };
+                assert_eq!(multiplicative_order(a, m), expected, "{} mod {}", a, m);
+            }
+        }
+    }
+
+    #[test]
+    fn test_crt_brute_force() {
+        for m1 in 1..20_u64 {
+            for m2 in 1..20 {
+                for r1 in 0..m1 {
+                    for r2 in 0..m2 {
+                        let lcm = m1.lcm(&m2);
+                        let expected = (0..lcm).find(|x| x % m1 == r1 && x % m2 == r2);
+                        let actual = crt(&[(r1, m1), (r2, m2)]);
+                        assert_eq!(
+                            actual,
+                            expected.map(|x| (x, lcm)),
+                            "{} {} {} {}",
+                            r1,
+                            m1,
+                            r2,
+                            m2
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mod_int() {
+        type M = ModInt<1_000_000_007>;
+        let a = M::new(123_456_789_012);
+        let b = M::new(987_654_321);
+        assert_eq!(a.value(), 123_456_789_012 % 1_000_000_007);
+        assert_eq!(a - a, M::zero());
+        assert_eq!((a - b) + b, a);
+        assert_eq!(a * a.inverse().unwrap(), M::one());
+        assert_eq!(M::new(2).pow(1_000_000_006), M::one());
+
+        // Big moduli mustn't overflow either.
+        type Big = ModInt<{ u64::MAX - 58 }>;
+        let x = Big::new(u64::MAX - 60);
+        assert_eq!((x + x).value(), u64::MAX - 62);
+        assert_eq!((x * x).value(), 4);
+        assert_eq!(ModInt::<1>::one(), ModInt::<1>::zero());
+    }
+
+    proptest! {
+        #[test]
+        fn pow_mod_big(a: u64, b in 0..1000_u64, m in 1..u64::MAX) {
+            let mut expected = 1 % m as u128;
+            for _ in 0..b {
+                expected = expected * (a as u128 % m as u128) % m as u128;
+            }
+            prop_assert_eq!(pow_mod(a, b, m) as u128, expected);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashSet;
+
+/// Where a value ends up when a map is applied to it over and over: after
+/// `tail` steps it reaches a cycle of `cycle_length` values, the smallest
+/// of which is `cycle_min`. A fixed point is a cycle of length 1.
+///
+/// The start is on the cycle itself when the tail is 0, and two values
+/// end up in the same cycle when they have the same `cycle_min`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Orbit<T> {
+    pub tail: usize,
+    pub cycle_length: usize,
+    pub cycle_min: T,
+}
+
+impl<T> Orbit<T> {
+    pub fn on_cycle(&self) -> bool {
+        self.tail == 0
+    }
+}
+
+/// Follows start, f(start), f(f(start)), ... until it repeats, using
+/// Brent's algorithm, which only keeps a couple of values at a time.
+///
+/// ```
+/// // Problem 92: 145 → 42 → 20 → 4 → 16 → 37 → 58 → 89 → 145.
+/// let square_digit_sum = |n: &u32| euler::digits(*n, 10).map(|d| d * d).sum::<u32>();
+/// let orbit = euler::find_cycle(13, square_digit_sum);
+/// assert_eq!((orbit.tail, orbit.cycle_length, orbit.cycle_min), (2, 1, 1));
+/// let orbit = euler::find_cycle(85, square_digit_sum);
+/// assert_eq!((orbit.tail, orbit.cycle_length, orbit.cycle_min), (1, 8, 4));
+/// ```
+///
+/// This never returns if the values never repeat.
+pub fn find_cycle<T, F>(start: T, mut f: F) -> Orbit<T>
+where
+    T: Clone + Ord,
+    F: FnMut(&T) -> T,
+{
+    // Find the cycle length, by moving the tortoise up to the hare
+    // whenever the hare has gone a power of 2 steps ahead.
+    let mut power = 1;
+    let mut cycle_length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = f(&start);
+    while tortoise != hare {
+        if power == cycle_length {
+            tortoise = hare.clone();
+            power *= 2;
+            cycle_length = 0;
+        }
+        hare = f(&hare);
+        cycle_length += 1;
+    }
+
+    // Then with the hare a cycle ahead, they meet at the start of it.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..cycle_length {
+        hare = f(&hare);
+    }
+    let mut tail = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        tail += 1;
+    }
+
+    let mut cycle_min = hare.clone();
+    for _ in 1..cycle_length {
+        hare = f(&hare);
+        cycle_min = cycle_min.min(hare.clone());
+    }
+    Orbit {
+        tail,
+        cycle_length,
+        cycle_min,
+    }
+}
+
+/// Finds the orbits of lots of values under the same map, remembering
+/// them for the values below `size`, so that each orbit only needs
+/// following until it meets one that's already known.
+///
+/// Values above the size still work; they just aren't remembered, so the
+/// map can wander off as far as it likes. Nothing recurses, so long
+/// orbits can't overflow the stack.
+///
+/// ```
+/// use euler::OrbitCache;
+///
+/// let collatz = |n: usize| if n % 2 == 0 { n / 2 } else { 3 * n + 1 };
+/// let mut cache = OrbitCache::new(collatz, 100);
+/// // 27 takes 111 steps to reach 1, which is 2 past the cycle 4 → 2 → 1.
+/// assert_eq!(cache.orbit(27).tail, 109);
+/// assert_eq!(cache.orbit(27).cycle_min, 1);
+/// assert!(cache.orbit(2).on_cycle());
+/// ```
+pub struct OrbitCache<F> {
+    f: F,
+    // The tail, and the index into cycles, kept small since there could
+    // be millions of them.
+    known: Vec<Option<(u32, u32)>>,
+    // The length and smallest value of each cycle found so far.
+    cycles: Vec<(usize, usize)>,
+    // Scratch space for the values on the current orbit.
+    path: Vec<usize>,
+}
+
+impl<F: FnMut(usize) -> usize> OrbitCache<F> {
+    pub fn new(f: F, size: usize) -> Self {
+        Self {
+            f,
+            known: vec![None; size],
+            cycles: vec![],
+            path: vec![],
+        }
+    }
+
+    pub fn orbit(&mut self, start: usize) -> Orbit<usize> {
+        let mut path = std::mem::take(&mut self.path);
+        path.clear();
+        let orbit = self.follow(start, &mut path);
+        self.path = path;
+        orbit
+    }
+
+    fn lookup(&self, n: usize) -> Option<(usize, u32)> {
+        let (tail, cycle) = self.known.get(n).copied().flatten()?;
+        Some((tail as usize, cycle))
+    }
+
+    fn remember(&mut self, n: usize, tail: usize, cycle: u32) {
+        if let (Some(slot), Ok(tail)) = (self.known.get_mut(n), u32::try_from(tail)) {
+            *slot = Some((tail, cycle));
+        }
+    }
+
+    fn make_orbit(&self, tail: usize, cycle: u32) -> Orbit<usize> {
+        let (cycle_length, cycle_min) = self.cycles[cycle as usize];
+        Orbit {
+            tail,
+            cycle_length,
+            cycle_min,
+        }
+    }
+
+    fn follow(&mut self, start: usize, path: &mut Vec<usize>) -> Orbit<usize> {
+        // Walk until either reaching a known orbit, or coming back round
+        // to a checkpoint value, with checkpoints moved along as in Brent's
+        // algorithm.
+        let mut n = start;
+        let mut checkpoint = start;
+        let mut power = 1;
+        let mut since_checkpoint = 0;
+        let (cycle_values, cycle) = loop {
+            if let Some((tail, cycle)) = self.lookup(n) {
+                if tail > 0 || path.is_empty() {
+                    for (i, &m) in path.iter().enumerate() {
+                        self.remember(m, tail + path.len() - i, cycle);
+                    }
+                    return self.make_orbit(tail + path.len(), cycle);
+                }
+                // Some of the path may be on this cycle too.
+                let cycle_length = self.cycles[cycle as usize].0;
+                break (self.cycle_from(n, cycle_length), cycle);
+            }
+            path.push(n);
+            let next = (self.f)(n);
+            since_checkpoint += 1;
+            if next == checkpoint {
+                let values = path[path.len() - since_checkpoint..].iter().copied();
+                let cycle_min = values.clone().min().unwrap();
+                self.cycles.push((since_checkpoint, cycle_min));
+                let cycle = u32::try_from(self.cycles.len() - 1).expect("Too many cycles");
+                break (values.collect(), cycle);
+            }
+            if since_checkpoint == power {
+                checkpoint = next;
+                power *= 2;
+                since_checkpoint = 0;
+            }
+            n = next;
+        };
+
+        // Everything from the first value on the cycle onwards is on it.
+        let on_cycle = path
+            .iter()
+            .position(|m| cycle_values.contains(m))
+            .unwrap_or(path.len());
+        for (i, &m) in path.iter().enumerate() {
+            self.remember(m, on_cycle.saturating_sub(i), cycle);
+        }
+        self.make_orbit(on_cycle, cycle)
+    }
+
+    fn cycle_from(&mut self, n: usize, cycle_length: usize) -> HashSet<usize> {
+        let mut cycle = HashSet::with_capacity(cycle_length);
+        let mut m = n;
+        for _ in 0..cycle_length {
+            cycle.insert(m);
+            m = (self.f)(m);
+        }
+        cycle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collatz(n: usize) -> usize {
+        if n.is_multiple_of(2) {
+            n / 2
+        } else {
+            3 * n + 1
+        }
+    }
+
+    #[test]
+    fn test_cache_matches_find_cycle() {
+        // A few maps with several cycles, some of which leave the cache.
+        let maps: [fn(usize) -> usize; 4] = [
+            collatz,
+            |n| n * n % 1000,
+            |n| (n * n + 1) % 997 + 500,
+            |n| if n > 50 { n - 50 } else { 3 * n + 7 },
+        ];
+        for f in maps {
+            let mut cache = OrbitCache::new(f, 300);
+            for start in (0..1000).rev() {
+                let expected = find_cycle(start, |&n| f(n));
+                assert_eq!(cache.orbit(start), expected, "start {}", start);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixed_points() {
+        let orbit = find_cycle(5, |&n| n);
+        assert_eq!((orbit.tail, orbit.cycle_length, orbit.cycle_min), (0, 1, 5));
+        let mut cache = OrbitCache::new(|n| n / 2, 10);
+        assert_eq!(cache.orbit(1000).tail, 10);
+        assert_eq!(cache.orbit(0).tail, 0);
+    }
+
+    #[test]
+    fn test_big_cycle() {
+        // 2 is a primitive root mod 1,000,003, so multiplying by it goes
+        // through all 1,000,002 non-zero values.
+        let f = |n: usize| n * 2 % 1_000_003;
+        let mut cache = OrbitCache::new(f, 10);
+        let orbit = cache.orbit(20);
+        assert_eq!(
+            (orbit.tail, orbit.cycle_length, orbit.cycle_min),
+            (0, 1_000_002, 1)
+        );
+        assert_eq!(cache.orbit(5), orbit);
+    }
+}
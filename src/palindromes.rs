@@ -0,0 +1,173 @@
+use std::ops::Range;
+
+use crate::reverse_digits;
+
+/// Iterates over all the palindromes with exactly `num_digits` digits
+/// in the given base, in increasing order. Use `.rev()` to get them in
+/// decreasing order instead.
+///
+/// Rather than testing every number, this generates the first half of
+/// the digits and mirrors it, so there are only about √n numbers to
+/// look at.
+///
+/// ```
+/// let ps = euler::palindromes(3, 10).take(4).collect::<Vec<_>>();
+/// assert_eq!(ps, [101, 111, 121, 131]);
+///
+/// let largest = euler::palindromes(4, 2).rev().next();
+/// assert_eq!(largest, Some(0b1111));
+/// ```
+///
+/// Panics if the palindromes won't fit in a u64.
+pub fn palindromes(num_digits: u32, base: u32) -> Palindromes {
+    if base < 2 {
+        panic!("Invalid base: {}", base);
+    }
+    if num_digits == 0 {
+        return Palindromes {
+            halves: 0..0,
+            odd: false,
+            base: base.into(),
+        };
+    }
+    let base = u64::from(base);
+
+    // The largest palindrome is base^num_digits - 1, which has to fit.
+    let fits = base
+        .checked_pow(num_digits - 1)
+        .and_then(|p| p.checked_mul(base - 1)?.checked_add(p - 1))
+        .is_some();
+    if !fits {
+        panic!("{}-digit palindromes don't fit in a u64", num_digits);
+    }
+
+    // Zero is the only palindrome with a leading zero.
+    let half_len = num_digits.div_ceil(2);
+    let start = if num_digits == 1 {
+        0
+    } else {
+        base.pow(half_len - 1)
+    };
+    let halves = start..base.pow(half_len);
+    let odd = num_digits % 2 == 1;
+    Palindromes { halves, odd, base }
+}
+
+pub struct Palindromes {
+    halves: Range<u64>,
+    odd: bool,
+    base: u64,
+}
+
+impl Palindromes {
+    fn mirror(&self, half: u64) -> u64 {
+        let mut n = half;
+        let mut rest = if self.odd { half / self.base } else { half };
+        while rest > 0 {
+            n = n * self.base + rest % self.base;
+            rest /= self.base;
+        }
+        n
+    }
+}
+
+impl Iterator for Palindromes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let half = self.halves.next()?;
+        Some(self.mirror(half))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.halves.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Palindromes {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let half = self.halves.next_back()?;
+        Some(self.mirror(half))
+    }
+}
+
+impl ExactSizeIterator for Palindromes {}
+
+/// Is n a palindrome when written in the given base?
+pub fn is_palindrome_in_base(n: u64, base: u32) -> bool {
+    reverse_digits(n, base) == n
+}
+
+/// Iterates in increasing order over the numbers below `limit` that are
+/// palindromes in both of two bases.
+///
+/// ```
+/// // Problem 36: palindromic in base 10 and base 2.
+/// let sum: u64 = euler::double_palindromes(1_000_000, 10, 2).sum();
+/// assert_eq!(sum, 872187);
+/// ```
+pub fn double_palindromes(limit: u64, base1: u32, base2: u32) -> impl Iterator<Item = u64> {
+    (1..)
+        .flat_map(move |num_digits| palindromes(num_digits, base1))
+        .take_while(move |&n| n < limit)
+        .filter(move |&n| is_palindrome_in_base(n, base2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::is_palindrome;
+
+    #[test]
+    fn test_matches_brute_force() {
+        let mut expected = (0..1_000_000)
+            .filter(|&n| is_palindrome(n))
+            .map(|n| n as u64);
+        for num_digits in 1..=6 {
+            for p in palindromes(num_digits, 10) {
+                assert_eq!(Some(p), expected.next());
+            }
+        }
+        assert_eq!(expected.next(), None);
+    }
+
+    #[test]
+    fn test_other_bases() {
+        for base in 2..=16 {
+            let limit = (base as u64).pow(5);
+            let expected = (0..limit)
+                .filter(|&n| is_palindrome_in_base(n, base))
+                .collect::<Vec<_>>();
+            let actual = (1..=5)
+                .flat_map(|d| palindromes(d, base))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "base {}", base);
+        }
+    }
+
+    #[test]
+    fn test_reversed() {
+        let forward = palindromes(7, 10).collect::<Vec<_>>();
+        let mut backward = palindromes(7, 10).rev().collect::<Vec<_>>();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(palindromes(7, 10).len(), 9000);
+        assert_eq!(palindromes(2, 10).next_back(), Some(99));
+    }
+
+    #[test]
+    fn test_limits() {
+        assert_eq!(palindromes(0, 10).next(), None);
+        assert_eq!(
+            palindromes(19, 10).next_back(),
+            Some(9_999_999_999_999_999_999)
+        );
+        assert_eq!(palindromes(64, 2).next_back(), Some(u64::MAX));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_big() {
+        palindromes(20, 10);
+    }
+}
@@ -0,0 +1,272 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use num_integer::Integer;
+use num_traits::FromPrimitive;
+use num_traits::ToPrimitive;
+
+use crate::digits;
+
+// Each decimal digit gets a 6 bit count, so the whole multiset fits in
+// 60 bits of a u64.
+const BITS: u32 = 6;
+const MAX_COUNT: u64 = (1 << BITS) - 1;
+
+/// The multiset of decimal digits in a number: how many 0s, how many
+/// 1s, and so on, packed into a single u64.
+///
+/// Two numbers have the same signature exactly when their digits are
+/// permutations of each other, so this makes a cheap HashMap key for
+/// grouping numbers by their digits.
+///
+/// ```
+/// use euler::DigitSignature;
+///
+/// let a = DigitSignature::of(1487);
+/// let b = DigitSignature::of(4817);
+/// assert_eq!(a, b);
+/// assert_eq!(a.sorted_digits(), [1, 4, 7, 8]);
+///
+/// // 192 × (1, 2, 3) concatenated is 192384576.
+/// let product = DigitSignature::of(192) + DigitSignature::of(384) + DigitSignature::of(576);
+/// assert!(product.is_pandigital(1..=9));
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DigitSignature(u64);
+
+impl DigitSignature {
+    /// The signature with no digits at all.
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    /// The signature of n's decimal digits. Zero has a single digit, 0.
+    ///
+    /// Panics if n is negative, or has more than 63 copies of any digit.
+    pub fn of<T>(n: T) -> Self
+    where
+        T: Integer + Clone + FromPrimitive + ToPrimitive,
+    {
+        Self::from_digits(digits(n, 10))
+    }
+
+    /// The signature of a sequence of decimal digits.
+    ///
+    /// Panics if any digit is more than 9, or appears more than 63 times.
+    pub fn from_digits<I: IntoIterator<Item = u32>>(digits: I) -> Self {
+        digits.into_iter().fold(Self::empty(), Self::with_digit)
+    }
+
+    /// Adds one more copy of digit d.
+    pub fn with_digit(self, d: u32) -> Self {
+        if d > 9 {
+            panic!("Not a decimal digit: {}", d);
+        }
+        if self.count(d) == MAX_COUNT as u32 {
+            panic!("Too many copies of digit {}", d);
+        }
+        Self(self.0 + (1 << (d * BITS)))
+    }
+
+    /// How many times digit d appears.
+    pub fn count(self, d: u32) -> u32 {
+        ((self.0 >> (d * BITS)) & MAX_COUNT) as u32
+    }
+
+    /// The total number of digits.
+    pub fn len(self) -> u32 {
+        (0..10).map(|d| self.count(d)).sum()
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Does this use each digit in the range exactly once, and no other
+    /// digits? The usual ranges are 1..=9 and 0..=9, but problems also
+    /// ask for things like 1..=n.
+    pub fn is_pandigital(self, range: RangeInclusive<u32>) -> bool {
+        // No number has digits past 9.
+        if !range.is_empty() && *range.end() > 9 {
+            return false;
+        }
+        let expected = range.fold(Self::empty(), Self::with_digit);
+        self == expected
+    }
+
+    /// The digits in increasing order, which is the smallest number that
+    /// can be made from them (with any zeros first).
+    pub fn sorted_digits(self) -> Vec<u32> {
+        (0..10)
+            .flat_map(|d| std::iter::repeat_n(d, self.count(d) as usize))
+            .collect()
+    }
+}
+
+/// Combines the digits of two numbers, as if they had been concatenated.
+///
+/// Panics if some digit then appears more than 63 times.
+impl std::ops::Add for DigitSignature {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        for d in 0..10 {
+            if u64::from(self.count(d) + other.count(d)) > MAX_COUNT {
+                panic!("Too many copies of digit {}", d);
+            }
+        }
+        Self(self.0 + other.0)
+    }
+}
+
+impl fmt::Debug for DigitSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.sorted_digits();
+        write!(f, "DigitSignature(")?;
+        for d in digits {
+            write!(f, "{}", d)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Does n use each digit in the range exactly once, and no others?
+///
+/// ```
+/// assert!(euler::is_pandigital(918273645_u64, 1..=9));
+/// assert!(euler::is_pandigital(1406357289_u64, 0..=9));
+/// assert!(!euler::is_pandigital(112345678_u64, 1..=9));
+/// assert!(euler::is_pandigital(4231, 1..=4));
+/// ```
+pub fn is_pandigital<T>(n: T, range: RangeInclusive<u32>) -> bool
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    // Bail out early if a digit is repeated, without building the whole
+    // signature, since that's the usual case when searching.
+    let mut seen = 0_u32;
+    for d in digits(n, 10) {
+        if !range.contains(&d) || seen & (1 << d) != 0 {
+            return false;
+        }
+        seen |= 1 << d;
+    }
+    seen.count_ones() as usize == range.count()
+}
+
+/// Are the decimal digits of a and b permutations of each other?
+///
+/// ```
+/// assert!(euler::is_digit_permutation(125874, 251748));
+/// assert!(!euler::is_digit_permutation(1223, 1233));
+/// ```
+pub fn is_digit_permutation<T>(a: T, b: T) -> bool
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    DigitSignature::of(a) == DigitSignature::of(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+    use proptest::prelude::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_counts() {
+        let sig = DigitSignature::of(1_000_223_u64);
+        assert_eq!(sig.count(0), 3);
+        assert_eq!(sig.count(1), 1);
+        assert_eq!(sig.count(2), 2);
+        assert_eq!(sig.count(3), 1);
+        assert_eq!(sig.count(9), 0);
+        assert_eq!(sig.len(), 7);
+        assert_eq!(DigitSignature::of(0).len(), 1);
+        assert!(DigitSignature::empty().is_empty());
+    }
+
+    #[test]
+    fn test_pandigital() {
+        assert!(DigitSignature::of(123456789).is_pandigital(1..=9));
+        assert!(!DigitSignature::of(123456789).is_pandigital(0..=9));
+        assert!(!DigitSignature::of(1234567890).is_pandigital(1..=9));
+        assert!(!is_pandigital(12345678, 1..=9));
+        assert!(!is_pandigital(1234567899_u64, 1..=9));
+        assert!(!is_pandigital(102345678, 1..=9));
+
+        // The method and the function agree, even past 9.
+        for n in [0_u64, 1, 21, 4231, 123456789, 1406357289, 10123456789] {
+            for range in [0..=9, 1..=9, 1..=4, 0..=10, 1..=10, 5..=12] {
+                assert_eq!(
+                    DigitSignature::of(n).is_pandigital(range.clone()),
+                    is_pandigital(n, range.clone()),
+                    "{} {:?}",
+                    n,
+                    range
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_grouping() {
+        // Problem 62: 41063625 (345³) is the smallest cube with exactly
+        // three cube permutations.
+        let mut groups: HashMap<DigitSignature, Vec<u64>> = HashMap::new();
+        for n in 1..1000_u64 {
+            let cube = n * n * n;
+            groups
+                .entry(DigitSignature::of(cube))
+                .or_default()
+                .push(cube);
+        }
+        let smallest = groups
+            .values()
+            .filter(|cubes| cubes.len() == 3)
+            .map(|cubes| cubes[0])
+            .min();
+        assert_eq!(smallest, Some(41063625));
+    }
+
+    #[test]
+    fn test_bigint() {
+        let n: BigInt = "98765432109876543210".parse().unwrap();
+        let sig = DigitSignature::of(n);
+        assert_eq!(
+            sig,
+            DigitSignature::of(9876543210_u64) + DigitSignature::of(1234567890_u64)
+        );
+        assert_eq!(format!("{:?}", sig), "DigitSignature(00112233445566778899)");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_too_many_digits() {
+        let ones: BigInt = "1".repeat(64).parse().unwrap();
+        DigitSignature::of(ones);
+    }
+
+    proptest! {
+        #[test]
+        fn signature_matches_sorted_string(a: u64, b: u64) {
+            let sorted = |n: u64| {
+                let mut cs = n.to_string().into_bytes();
+                cs.sort();
+                cs
+            };
+            prop_assert_eq!(is_digit_permutation(a, b), sorted(a) == sorted(b));
+            let ds = DigitSignature::of(a).sorted_digits();
+            let expected = sorted(a).iter().map(|c| u32::from(c - b'0')).collect::<Vec<_>>();
+            prop_assert_eq!(ds, expected);
+        }
+
+        #[test]
+        fn pandigital_agrees(n in 0..2_000_000_000_u64, hi in 1..=9_u32) {
+            let sig = DigitSignature::of(n);
+            prop_assert_eq!(is_pandigital(n, 1..=hi), sig.is_pandigital(1..=hi));
+            prop_assert_eq!(is_pandigital(n, 0..=hi), sig.is_pandigital(0..=hi));
+        }
+    }
+}
@@ -1,5 +1,9 @@
 use std::iter::from_fn;
 
+use num_bigint::BigInt;
+use num_traits::One;
+use num_traits::Zero;
+
 /// Iterator over all triples (a, b, c) where a + b + c == n and
 /// a < b < c.
 pub fn partitions3(n: i64) -> impl Iterator<Item = (i64, i64, i64)> {
@@ -24,9 +28,335 @@ pub fn partitions3(n: i64) -> impl Iterator<Item = (i64, i64, i64)> {
     })
 }
 
+/// Enumerates the partitions of n into exactly k parts, each written in
+/// increasing order, in lexicographic order. By default parts can repeat
+/// and range from 1 to n, but the builder methods can change that.
+///
+/// To avoid allocating for every partition, this isn't an Iterator;
+/// `next_partition` hands out a slice instead.
+///
+/// ```
+/// let mut parts = euler::partitions_k(8, 3);
+/// let mut all = vec![];
+/// while let Some(p) = parts.next_partition() {
+///     all.push(p.to_vec());
+/// }
+/// assert_eq!(all, [[1, 1, 6], [1, 2, 5], [1, 3, 4], [2, 2, 4], [2, 3, 3]]);
+///
+/// let mut parts = euler::partitions_k(8, 3).strict();
+/// assert_eq!(parts.next_partition(), Some(&[1, 2, 5][..]));
+/// assert_eq!(parts.next_partition(), Some(&[1, 3, 4][..]));
+/// assert_eq!(parts.next_partition(), None);
+/// ```
+pub fn partitions_k(n: u64, k: usize) -> PartitionsK {
+    PartitionsK {
+        n,
+        k,
+        strict: false,
+        min_part: 1,
+        max_part: n,
+        parts: vec![],
+        started: false,
+        done: false,
+    }
+}
+
+pub struct PartitionsK {
+    n: u64,
+    k: usize,
+    strict: bool,
+    min_part: u64,
+    max_part: u64,
+    parts: Vec<u64>,
+    started: bool,
+    done: bool,
+}
+
+impl PartitionsK {
+    /// Only allows partitions with no repeated parts.
+    pub fn strict(self) -> Self {
+        Self {
+            strict: true,
+            ..self
+        }
+    }
+
+    /// Sets the smallest allowed part.
+    pub fn min_part(self, min_part: u64) -> Self {
+        Self { min_part, ..self }
+    }
+
+    /// Sets the largest allowed part.
+    ///
+    /// ```
+    /// // 10 = 2 + 4 + 4 = 3 + 3 + 4 with parts from 2 to 4.
+    /// let mut parts = euler::partitions_k(10, 3).min_part(2).max_part(4);
+    /// assert_eq!(parts.next_partition(), Some(&[2, 4, 4][..]));
+    /// assert_eq!(parts.next_partition(), Some(&[3, 3, 4][..]));
+    /// assert_eq!(parts.next_partition(), None);
+    /// ```
+    pub fn max_part(self, max_part: u64) -> Self {
+        Self { max_part, ..self }
+    }
+
+    /// Moves on to the next partition, and returns it.
+    pub fn next_partition(&mut self) -> Option<&[u64]> {
+        if self.done {
+            return None;
+        }
+        let found = if self.started {
+            self.advance()
+        } else {
+            self.started = true;
+            self.parts.resize(self.k, 0);
+            self.fill(0, self.min_part)
+        };
+        self.done = !found;
+        found.then_some(&self.parts[..])
+    }
+
+    fn gap(&self) -> u128 {
+        u128::from(self.strict)
+    }
+
+    // Whether j parts, the first at least `lowest`, can add up to total.
+    fn possible(&self, total: u128, j: usize, lowest: u128) -> bool {
+        if j == 0 {
+            return total == 0;
+        }
+        let j = j as u128;
+        let spread = self.gap() * j * (j - 1) / 2;
+        let (least, most) = (
+            j * lowest + spread,
+            (j * self.max_part as u128).checked_sub(spread),
+        );
+        most.is_some_and(|most| least <= total && total <= most)
+    }
+
+    // Fills in the parts from i on with the smallest values that still
+    // leave a partition, given that parts[i] is at least `lowest`. Leaves
+    // them alone and returns false if there's no such partition.
+    fn fill(&mut self, i: usize, lowest: u64) -> bool {
+        let k = self.k;
+        let used = self.parts[..i].iter().map(|&p| p as u128).sum::<u128>();
+        let Some(mut rest) = (self.n as u128).checked_sub(used) else {
+            return false;
+        };
+        if !self.possible(rest, k - i, lowest as u128) {
+            return false;
+        }
+        let mut lowest = lowest as u128;
+        for j in i..k {
+            // As small as possible, while leaving little enough for the
+            // parts after it to manage.
+            let remaining = (k - j - 1) as u128;
+            let spread = self.gap() * remaining * remaining.saturating_sub(1) / 2;
+            let most_after = (remaining * self.max_part as u128).saturating_sub(spread);
+            let part = lowest.max(rest.saturating_sub(most_after));
+            self.parts[j] = part as u64;
+            rest -= part;
+            lowest = part + self.gap();
+        }
+        true
+    }
+
+    // Bumps up the last part that can go up, and refills the rest.
+    fn advance(&mut self) -> bool {
+        (0..self.k.saturating_sub(1))
+            .rev()
+            .any(|i| self.parts[i] < self.max_part && self.fill(i, self.parts[i] + 1))
+    }
+}
+
+/// The number of partitions p(n): the ways of writing n as a sum of
+/// positive integers, ignoring order.
+///
+/// This uses Euler's pentagonal number theorem, which gives the
+/// recurrence
+///
+///   p(n) = p(n - 1) + p(n - 2) - p(n - 5) - p(n - 7) + p(n - 12) + ...
+///
+/// where the offsets are the generalised pentagonal numbers k(3k ∓ 1)/2,
+/// and the signs go in pairs. That takes O(n^1.5) steps.
+///
+/// ```
+/// // Problem 76 asks for this, minus the partition with only one part.
+/// assert_eq!(euler::partition_count(100), 190569292.into());
+/// ```
+pub fn partition_count(n: usize) -> BigInt {
+    let mut p = vec![BigInt::one()];
+    for m in 1..=n {
+        let mut total = BigInt::zero();
+        for (offset, add) in pentagonal_offsets(m) {
+            if add {
+                total += &p[m - offset];
+            } else {
+                total -= &p[m - offset];
+            }
+        }
+        p.push(total);
+    }
+    p.pop().unwrap()
+}
+
+/// Iterates over p(0), p(1), p(2), ... mod m.
+///
+/// ```
+/// let p = euler::partition_counts_mod(1000).take(8).collect::<Vec<_>>();
+/// assert_eq!(p, [1, 1, 2, 3, 5, 7, 11, 15]);
+/// // The first p(n) divisible by 7.
+/// assert_eq!(euler::partition_counts_mod(7).position(|p| p == 0), Some(5));
+/// ```
+///
+/// Panics if m is 0.
+pub fn partition_counts_mod(m: u64) -> PartitionsMod {
+    if m == 0 {
+        panic!("Partition counts mod 0");
+    }
+    PartitionsMod { p: vec![], m }
+}
+
+pub struct PartitionsMod {
+    p: Vec<u64>,
+    m: u64,
+}
+
+impl Iterator for PartitionsMod {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let n = self.p.len();
+        // There are only O(√n) terms, so they can't overflow a u128.
+        let (mut plus, mut minus) = (if n == 0 { 1 } else { 0 }, 0);
+        for (offset, add) in pentagonal_offsets(n) {
+            let term = self.p[n - offset] as u128;
+            if add {
+                plus += term;
+            } else {
+                minus += term;
+            }
+        }
+        let m = self.m as u128;
+        let total = ((plus % m + m - minus % m) % m) as u64;
+        self.p.push(total);
+        Some(total)
+    }
+}
+
+// The generalised pentagonal numbers up to n, with whether each one is
+// added or subtracted in the pentagonal recurrence.
+fn pentagonal_offsets(n: usize) -> impl Iterator<Item = (usize, bool)> {
+    (1..)
+        .flat_map(|k| [k * (3 * k - 1) / 2, k * (3 * k + 1) / 2].map(|g| (g, k % 2 == 1)))
+        .take_while(move |&(g, _)| g <= n)
+}
+
+/// The number of ways of writing n as a sum of the given coin values,
+/// each of which can be used any number of times, ignoring order.
+///
+/// ```
+/// // Problem 31: ways of making £2 out of British coins.
+/// let coins = [1, 2, 5, 10, 20, 50, 100, 200];
+/// assert_eq!(euler::coin_partition_count(200, &coins), 73682.into());
+/// ```
+pub fn coin_partition_count(n: usize, coins: &[usize]) -> BigInt {
+    // ways[i] counts the sums to i using the coins so far.
+    let mut ways = vec![BigInt::zero(); n + 1];
+    ways[0] = BigInt::one();
+    for &coin in coins.iter().filter(|&&c| c > 0) {
+        for i in coin..=n {
+            let (done, rest) = ways.split_at_mut(i);
+            rest[0] += &done[i - coin];
+        }
+    }
+    ways.swap_remove(n)
+}
+
+/// The number of partitions of n into at most k parts, which is also
+/// the number with no part bigger than k.
+///
+/// ```
+/// // 4, 3 + 1, 2 + 2 and 2 + 1 + 1
+/// assert_eq!(euler::partition_count_at_most_k(5, 2), 3.into());
+/// ```
+pub fn partition_count_at_most_k(n: usize, k: usize) -> BigInt {
+    coin_partition_count(n, &(1..=k).collect::<Vec<_>>())
+}
+
+/// The number of partitions of n into odd parts.
+///
+/// ```
+/// // 5, 3 + 1 + 1 and 1 + 1 + 1 + 1 + 1
+/// assert_eq!(euler::odd_partition_count(5), 3.into());
+/// ```
+pub fn odd_partition_count(n: usize) -> BigInt {
+    coin_partition_count(n, &(1..=n).step_by(2).collect::<Vec<_>>())
+}
+
+/// The number of partitions of n into distinct parts. Euler showed that
+/// this is always the same as `odd_partition_count`.
+///
+/// ```
+/// // 5, 4 + 1 and 3 + 2
+/// assert_eq!(euler::distinct_partition_count(5), 3.into());
+/// ```
+pub fn distinct_partition_count(n: usize) -> BigInt {
+    // Like coins, but each part can only be used once, so go downwards
+    // to avoid reusing the part just added.
+    let mut ways = vec![BigInt::zero(); n + 1];
+    ways[0] = BigInt::one();
+    for part in 1..=n {
+        for i in (part..=n).rev() {
+            let (done, rest) = ways.split_at_mut(i);
+            rest[0] += &done[i - part];
+        }
+    }
+    ways.swap_remove(n)
+}
+
+/// Iterates over the partitions of n, each with its parts in
+/// non-increasing order, in lexicographic order, so from 1 + 1 + ... + 1
+/// up to n itself.
+///
+/// ```
+/// let p4 = euler::partitions(4).collect::<Vec<_>>();
+/// assert_eq!(p4, [vec![1, 1, 1, 1], vec![2, 1, 1], vec![2, 2], vec![3, 1], vec![4]]);
+/// ```
+pub fn partitions(n: usize) -> Partitions {
+    Partitions {
+        next: Some(vec![1; n]),
+    }
+}
+
+pub struct Partitions {
+    next: Option<Vec<usize>>,
+}
+
+impl Iterator for Partitions {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let current = self.next.take()?;
+        // Find the last part that can go up by one, taking the one from
+        // the parts after it, then spread the rest of those out as 1s.
+        let i = (0..current.len().saturating_sub(1))
+            .rev()
+            .find(|&i| i == 0 || current[i] < current[i - 1]);
+        if let Some(i) = i {
+            let rest = current[i + 1..].iter().sum::<usize>() - 1;
+            let mut next = current[..=i].to_vec();
+            next[i] += 1;
+            next.resize(i + 1 + rest, 1);
+            self.next = Some(next);
+        }
+        Some(current)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_partition3() {
@@ -54,4 +384,120 @@ mod tests {
         assert_eq!(p11.next().unwrap(), (2, 4, 5));
         assert!(p11.next().is_none());
     }
+
+    #[test]
+    fn test_counts_match_enumeration() {
+        for n in 0..25 {
+            let all = partitions(n).collect::<Vec<_>>();
+            assert_eq!(partition_count(n), all.len().into(), "p({})", n);
+
+            let distinct = all.iter().filter(|p| p.windows(2).all(|w| w[0] > w[1]));
+            assert_eq!(distinct_partition_count(n), distinct.count().into());
+            let odd = all.iter().filter(|p| p.iter().all(|x| x % 2 == 1));
+            assert_eq!(odd_partition_count(n), odd.count().into());
+            for k in 0..=n {
+                let at_most_k = all.iter().filter(|p| p.len() <= k);
+                assert_eq!(partition_count_at_most_k(n, k), at_most_k.count().into());
+            }
+        }
+    }
+
+    #[test]
+    fn test_lexicographic() {
+        for n in 0..20 {
+            let all = partitions(n).collect::<Vec<_>>();
+            assert!(all.windows(2).all(|w| w[0] < w[1]));
+            for p in &all {
+                assert_eq!(p.iter().sum::<usize>(), n);
+                assert!(p.windows(2).all(|w| w[0] >= w[1]));
+            }
+        }
+        assert_eq!(partitions(0).collect::<Vec<_>>(), [vec![]]);
+    }
+
+    #[test]
+    fn test_distinct_equals_odd() {
+        for n in 0..200 {
+            assert_eq!(distinct_partition_count(n), odd_partition_count(n));
+        }
+    }
+
+    #[test]
+    fn test_mod_matches_exact() {
+        let m = 987_654_321_u64;
+        for (n, p) in partition_counts_mod(m).take(500).enumerate() {
+            assert_eq!(BigInt::from(p), partition_count(n) % m);
+        }
+        assert!(partition_counts_mod(1).take(10).all(|p| p == 0));
+        assert_eq!(
+            partition_count(1000).to_string(),
+            "24061467864032622473692149727991"
+        );
+    }
+
+    #[test]
+    fn test_coins() {
+        assert_eq!(coin_partition_count(0, &[]), 1.into());
+        assert_eq!(coin_partition_count(5, &[]), 0.into());
+        assert_eq!(coin_partition_count(10, &[3, 0, 7]), 1.into());
+        assert_eq!(
+            coin_partition_count(100, &[1, 5, 10, 25, 50, 100]),
+            293.into()
+        );
+    }
+
+    #[test]
+    fn test_partitions_k_bounds() {
+        let all = |mut parts: PartitionsK| {
+            let mut all = vec![];
+            while let Some(p) = parts.next_partition() {
+                all.push(p.to_vec());
+            }
+            all
+        };
+        assert_eq!(all(partitions_k(0, 0)), [vec![]]);
+        assert!(all(partitions_k(5, 0)).is_empty());
+        assert!(all(partitions_k(2, 3)).is_empty());
+        assert_eq!(all(partitions_k(0, 3).min_part(0)), [vec![0, 0, 0]]);
+        assert_eq!(
+            all(partitions_k(3, 3).min_part(0).strict()),
+            [vec![0, 1, 2]]
+        );
+        assert_eq!(
+            all(partitions_k(12, 3).min_part(4).max_part(4)),
+            [vec![4, 4, 4]]
+        );
+        assert!(all(partitions_k(12, 3).min_part(5)).is_empty());
+        assert!(all(partitions_k(12, 3).max_part(3)).is_empty());
+        assert_eq!(all(partitions_k(7, 1).max_part(7)), [vec![7]]);
+
+        // The same as partitions3, which is strict.
+        let triples = all(partitions_k(30, 3).strict());
+        let expected = partitions3(30).map(|(a, b, c)| vec![a as u64, b as u64, c as u64]);
+        assert_eq!(triples, expected.collect::<Vec<_>>());
+    }
+
+    proptest! {
+        #[test]
+        fn partitions_k_counts(n in 0..40_u64) {
+            let (mut total, mut strict_total) = (0_usize, 0_usize);
+            for k in 0..=n as usize {
+                let mut parts = partitions_k(n, k);
+                let mut previous: Option<Vec<u64>> = None;
+                while let Some(p) = parts.next_partition() {
+                    prop_assert_eq!(p.iter().sum::<u64>(), n);
+                    prop_assert!(p.windows(2).all(|w| w[0] <= w[1]));
+                    prop_assert!(previous.as_deref() < Some(p));
+                    previous = Some(p.to_vec());
+                    total += 1;
+                }
+                let mut parts = partitions_k(n, k).strict();
+                while parts.next_partition().is_some() {
+                    strict_total += 1;
+                }
+            }
+            prop_assert_eq!(BigInt::from(total), partition_count(n as usize));
+            prop_assert_eq!(BigInt::from(strict_total), distinct_partition_count(n as usize));
+        }
+    }
 }
@@ -0,0 +1,281 @@
+use itertools::Itertools;
+use num_integer::Integer;
+use num_integer::Roots;
+use num_traits::FromPrimitive;
+use num_traits::ToPrimitive;
+
+use crate::digits;
+use crate::from_digits;
+
+/// A decimal number with some of its digits hidden, written like
+/// "1_2_3_4_5_6_7_8_9_0": each digit is either fixed, or an `_` that
+/// can be any digit. A matching number has exactly as many digits as
+/// the pattern, so a leading `_` can't be zero.
+///
+/// ```
+/// use euler::DigitPattern;
+///
+/// let pattern = DigitPattern::new("1_3");
+/// assert!(pattern.matches(&183));
+/// assert!(!pattern.matches(&1830));
+///
+/// let primes = pattern.numbers::<u64>().filter(|&n| primal::is_prime(n));
+/// assert_eq!(primes.collect::<Vec<_>>(), [103, 113, 163, 173, 193]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DigitPattern {
+    // Most significant digit first; None is a wildcard.
+    digits: Vec<Option<u32>>,
+}
+
+impl DigitPattern {
+    /// Panics if the pattern is empty, contains anything other than
+    /// digits and `_`, or starts with a fixed 0.
+    pub fn new(pattern: &str) -> Self {
+        let digits = pattern
+            .chars()
+            .map(|c| match c {
+                '_' => None,
+                c => Some(c.to_digit(10).expect("Invalid character in digit pattern")),
+            })
+            .collect::<Vec<_>>();
+        if digits.is_empty() {
+            panic!("Empty digit pattern");
+        }
+        if digits.len() > 1 && digits[0] == Some(0) {
+            panic!("Digit pattern can't start with 0: {}", pattern);
+        }
+        Self { digits }
+    }
+
+    /// The number of digits in a matching number.
+    pub fn len(&self) -> usize {
+        self.digits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Does n fit the pattern?
+    pub fn matches<T>(&self, n: &T) -> bool
+    where
+        T: Integer + Clone + FromPrimitive + ToPrimitive,
+    {
+        if *n < T::zero() {
+            return false;
+        }
+        let mut ds = digits(n.clone(), 10);
+        let all_match = (0..self.len()).all(|i| match ds.next() {
+            Some(d) => self.digit_matches(i, d),
+            None => false,
+        });
+        all_match && ds.next().is_none()
+    }
+
+    /// Does digit d fit at the given place, counting from the right?
+    fn digit_matches(&self, place: usize, d: u32) -> bool {
+        match self.digits[self.len() - 1 - place] {
+            Some(fixed) => fixed == d,
+            None => true,
+        }
+    }
+
+    /// The smallest number that fits the pattern.
+    pub fn min<T>(&self) -> T
+    where
+        T: Integer + Clone + FromPrimitive + ToPrimitive,
+    {
+        let leading_wildcard = self.len() > 1 && self.digits[0].is_none();
+        let ds = self.digits.iter().enumerate().map(|(i, d)| match d {
+            Some(d) => *d,
+            None if i == 0 && leading_wildcard => 1,
+            None => 0,
+        });
+        from_digits(ds, 10)
+    }
+
+    /// The largest number that fits the pattern.
+    pub fn max<T>(&self) -> T
+    where
+        T: Integer + Clone + FromPrimitive + ToPrimitive,
+    {
+        from_digits(self.digits.iter().map(|d| d.unwrap_or(9)), 10)
+    }
+
+    /// Iterates in increasing order over every number that fits the
+    /// pattern. There are 10 of these for each wildcard, so this is only
+    /// practical for a handful of wildcards; filter it to search for
+    /// numbers with some other property.
+    ///
+    /// The largest match has to fit in T.
+    pub fn numbers<T>(&self) -> impl Iterator<Item = T> + '_
+    where
+        T: Integer + Clone + FromPrimitive + ToPrimitive,
+    {
+        let choices = self
+            .digits
+            .iter()
+            .enumerate()
+            .map(|(i, d)| match d {
+                Some(d) => *d..*d + 1,
+                None if i == 0 && self.len() > 1 => 1..10,
+                None => 0..10,
+            })
+            .multi_cartesian_product();
+        choices.map(|ds| from_digits(ds, 10))
+    }
+
+    /// Finds every r whose kth power fits the pattern, in increasing
+    /// order.
+    ///
+    /// Rather than trying every r, this builds r up from its last digit.
+    /// The last j digits of r^k only depend on the last j digits of r, so
+    /// each new digit of r can be checked against the next digit of the
+    /// pattern straight away, and most choices are dropped early. For a
+    /// pattern like "1_2_3_4_5_6_7_8_9_0" this looks at roughly the
+    /// square root of the numbers a direct search would.
+    ///
+    /// ```
+    /// use euler::DigitPattern;
+    ///
+    /// let squares = DigitPattern::new("1_2_3_4_5_6_7_8_9_0");
+    /// assert_eq!(squares.kth_power_roots::<u64>(2), [1389019170]);
+    ///
+    /// let cubes = DigitPattern::new("_7__3");
+    /// for r in cubes.kth_power_roots::<u32>(3) {
+    ///     assert!(cubes.matches(&r.pow(3)));
+    /// }
+    /// ```
+    ///
+    /// The largest match has to fit in T; use u128 or BigInt for longer
+    /// patterns.
+    pub fn kth_power_roots<T>(&self, k: u32) -> Vec<T>
+    where
+        T: Integer + Clone + FromPrimitive + ToPrimitive + Roots,
+    {
+        if k == 0 {
+            panic!("Can't take 0th roots");
+        }
+        let root_max = self.max::<T>().nth_root(k);
+        let ten = T::from_u32(10).unwrap();
+
+        let mut roots = vec![];
+        // The possible last j digits of a root, all of which have kth
+        // powers that fit the last j digits of the pattern.
+        let mut suffixes = vec![T::zero()];
+        let mut place = T::one();
+        for j in 0..self.len() {
+            if j > 0 && place > root_max {
+                break;
+            }
+            let mut next_suffixes = vec![];
+            for suffix in &suffixes {
+                for d in 0..10 {
+                    let r = T::from_u32(d).unwrap() * place.clone() + suffix.clone();
+                    if r > root_max {
+                        break;
+                    }
+                    let power = num_traits::pow(r.clone(), k as usize);
+                    let digit = (power.clone() / place.clone()) % ten.clone();
+                    if !self.digit_matches(j, digit.to_u32().unwrap()) {
+                        continue;
+                    }
+                    // Only check r once, when we add its leading digit.
+                    if (d != 0 || j == 0) && self.matches(&power) {
+                        roots.push(r.clone());
+                    }
+                    next_suffixes.push(r);
+                }
+            }
+            suffixes = next_suffixes;
+            if j + 1 < self.len() {
+                place = place * ten.clone();
+            }
+        }
+        roots.sort();
+        roots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigInt;
+
+    #[test]
+    fn test_matches() {
+        let pattern = DigitPattern::new("_0_");
+        assert!(pattern.matches(&101));
+        assert!(pattern.matches(&909));
+        assert!(!pattern.matches(&9));
+        assert!(!pattern.matches(&111));
+        assert!(!pattern.matches(&1001));
+        assert!(!pattern.matches(&-101));
+        assert_eq!(pattern.min::<u32>(), 100);
+        assert_eq!(pattern.max::<u32>(), 909);
+        assert_eq!(pattern.numbers::<u32>().count(), 90);
+
+        let single = DigitPattern::new("_");
+        assert_eq!(
+            single.numbers::<u32>().collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+        assert!(single.matches(&0));
+    }
+
+    #[test]
+    fn test_no_wildcards() {
+        let pattern = DigitPattern::new("144");
+        assert_eq!(pattern.numbers::<u32>().collect::<Vec<_>>(), [144]);
+        assert_eq!(pattern.kth_power_roots::<u32>(2), [12]);
+        assert_eq!(pattern.kth_power_roots::<u32>(3), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_roots_match_brute_force() {
+        let patterns = [
+            "_", "0", "1", "__", "_6", "1_", "__1", "_2_", "1__4", "____", "_0_0_", "__9__",
+            "6__4__",
+        ];
+        for text in patterns {
+            let pattern = DigitPattern::new(text);
+            for k in 1..=4 {
+                let expected = (0..1_000_000_u64)
+                    .take_while(|r| r.pow(k) <= pattern.max())
+                    .filter(|r| pattern.matches(&r.pow(k)))
+                    .collect::<Vec<_>>();
+                assert_eq!(
+                    pattern.kth_power_roots::<u64>(k),
+                    expected,
+                    "{} k={}",
+                    text,
+                    k
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_patterns() {
+        // Hide every other digit of a 21 digit square, too big for u64.
+        let root: u128 = 12345678901;
+        let square = (root * root).to_string();
+        let hidden = square
+            .chars()
+            .enumerate()
+            .map(|(i, c)| if i % 2 == 1 { '_' } else { c })
+            .collect::<String>();
+        let pattern = DigitPattern::new(&hidden);
+        assert!(pattern.kth_power_roots::<u128>(2).contains(&root));
+        let big_roots = pattern.kth_power_roots::<BigInt>(2);
+        assert!(big_roots.contains(&BigInt::from(root)));
+        assert!(big_roots.iter().all(|r| pattern.matches(&(r * r))));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_leading_zero() {
+        DigitPattern::new("0_1");
+    }
+}
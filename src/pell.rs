@@ -0,0 +1,309 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashSet;
+
+use num_bigint::BigInt;
+use num_traits::One;
+use num_traits::Signed;
+use num_traits::Zero;
+
+use crate::continued_fractions::surd_floor;
+use crate::convergents;
+use crate::isqrt;
+use crate::sqrt_cf;
+
+/// Finds the fundamental (smallest positive) solution of Pell's
+/// equation x² − Dy² = 1.
+///
+/// Returns None if D is a perfect square, because then the only
+/// solution is the trivial one, x = 1, y = 0.
+///
+/// ```
+/// use num_bigint::BigInt;
+///
+/// let (x, y) = euler::pell_fundamental(61).unwrap();
+/// assert_eq!(x, BigInt::from(1766319049));
+/// assert_eq!(y, BigInt::from(226153980));
+/// ```
+pub fn pell_fundamental(d: u64) -> Option<(BigInt, BigInt)> {
+    let (x, y) = fundamental_unit(d)?;
+    if &x * &x - BigInt::from(d) * &y * &y == BigInt::one() {
+        Some((x, y))
+    } else {
+        // The unit has norm -1, so its square has norm 1.
+        Some(square_in(d, &x, &y))
+    }
+}
+
+/// Finds the fundamental solution of the negative Pell equation
+/// x² − Dy² = −1, if there is one. There is a solution exactly
+/// when the continued fraction of √D has an odd period.
+pub fn negative_pell_fundamental(d: u64) -> Option<(BigInt, BigInt)> {
+    let (x, y) = fundamental_unit(d)?;
+    if &x * &x - BigInt::from(d) * &y * &y == -BigInt::one() {
+        Some((x, y))
+    } else {
+        None
+    }
+}
+
+/// Finds one fundamental solution of x² − Dy² = N for each class of
+/// solutions, using the LMM algorithm (Lagrange, Matthews, Mollin).
+/// Every other solution is one of these multiplied by a power of the
+/// fundamental solution of x² − Dy² = 1, up to sign.
+///
+/// The list is empty if there are no solutions at all. Takes time
+/// proportional to |N|, so it's not for enormous N.
+///
+/// Panics if D is a perfect square or N is zero.
+pub fn pell_fundamental_solutions(d: u64, n: i64) -> Vec<(BigInt, BigInt)> {
+    if n == 0 {
+        panic!("Can only solve x² - Dy² = N for non-zero N");
+    }
+    let unit = fundamental_unit(d).unwrap_or_else(|| panic!("{} is a perfect square", d));
+    let d_big = BigInt::from(d);
+    let negative_unit = if &unit.0 * &unit.0 - &d_big * &unit.1 * &unit.1 == -BigInt::one() {
+        Some(unit)
+    } else {
+        None
+    };
+
+    let mut solutions = vec![];
+    let mut f: i64 = 1;
+    while f * f <= n.abs() {
+        if n % (f * f) == 0 {
+            let m = n / (f * f);
+            let abs_m = m.abs();
+            let f_big = BigInt::from(f);
+            // z ranges over (-|m|/2, |m|/2] with z² = D (mod |m|).
+            for z in (-abs_m + 1).div_euclid(2)..=abs_m / 2 {
+                if (z as i128 * z as i128 - d as i128).rem_euclid(abs_m as i128) != 0 {
+                    continue;
+                }
+                let (r, s) = match pqa_solution(z, abs_m, d) {
+                    Some(rs) => rs,
+                    None => continue,
+                };
+                let norm = &r * &r - &d_big * &s * &s;
+                if norm == BigInt::from(m) {
+                    solutions.push((&f_big * r, &f_big * s));
+                } else if let Some((t, u)) = &negative_unit {
+                    // r² - Ds² = -m, so multiply by the norm -1 unit.
+                    let x = &r * t + &s * &d_big * u;
+                    let y = &r * u + &s * t;
+                    solutions.push((&f_big * x, &f_big * y));
+                }
+            }
+        }
+        f += 1;
+    }
+    solutions
+}
+
+/// Iterates over all solutions of x² − Dy² = N with x and y both
+/// non-negative, in increasing order of x.
+///
+/// For N = 1, the first solution is the trivial (1, 0).
+///
+/// ```
+/// use num_bigint::BigInt;
+///
+/// let solutions = euler::pell_solutions(2, 7)
+///     .take(4)
+///     .map(|(x, y)| (x.try_into().unwrap(), y.try_into().unwrap()))
+///     .collect::<Vec<(u64, u64)>>();
+/// assert_eq!(solutions, [(3, 1), (5, 3), (13, 9), (27, 19)]);
+/// ```
+///
+/// Panics if D is a perfect square or N is zero.
+pub fn pell_solutions(d: u64, n: i64) -> PellSolutions {
+    let (t, u) = pell_fundamental(d).unwrap_or_else(|| panic!("{} is a perfect square", d));
+    let fundamental_solutions = pell_fundamental_solutions(d, n);
+    let d = BigInt::from(d);
+
+    let mut starts = HashSet::new();
+    for (r, s) in fundamental_solutions {
+        let (mut x, mut y) = if is_positive(&r, &s, &d) {
+            (r, s)
+        } else {
+            (-r, -s)
+        };
+
+        // Within a class, the solutions with x, y >= 0 are exactly the
+        // ones from some point onwards, so find the first of them.
+        while x.is_negative() || y.is_negative() {
+            (x, y) = (&x * &t + &d * &y * &u, &x * &u + &y * &t);
+        }
+        loop {
+            let prev = (&x * &t - &d * &y * &u, &y * &t - &x * &u);
+            if prev.0.is_negative() || prev.1.is_negative() {
+                break;
+            }
+            (x, y) = prev;
+        }
+        starts.insert((x, y));
+    }
+
+    PellSolutions {
+        d,
+        unit: (t, u),
+        queue: starts.into_iter().map(Reverse).collect(),
+    }
+}
+
+pub struct PellSolutions {
+    d: BigInt,
+    unit: (BigInt, BigInt),
+    queue: BinaryHeap<Reverse<(BigInt, BigInt)>>,
+}
+
+impl Iterator for PellSolutions {
+    type Item = (BigInt, BigInt);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((x, y)) = self.queue.pop()?;
+        let (t, u) = &self.unit;
+        let next = (&x * t + &self.d * &y * u, &x * u + &y * t);
+        self.queue.push(Reverse(next));
+        Some((x, y))
+    }
+}
+
+/// Finds the solution of x² − Dy² = ±1 with the smallest positive x,
+/// which is the last convergent of the first period of √D.
+fn fundamental_unit(d: u64) -> Option<(BigInt, BigInt)> {
+    let cf = sqrt_cf(d);
+    if cf.period.is_empty() {
+        return None;
+    }
+    let unit = convergents(cf.terms()).nth(cf.period.len() - 1);
+    unit
+}
+
+/// Squares x + y√d.
+fn square_in(d: u64, x: &BigInt, y: &BigInt) -> (BigInt, BigInt) {
+    (x * x + BigInt::from(d) * y * y, 2 * x * y)
+}
+
+/// Is r + s√d > 0?
+fn is_positive(r: &BigInt, s: &BigInt, d: &BigInt) -> bool {
+    match (r.is_negative(), s.is_negative()) {
+        (false, false) => !r.is_zero() || !s.is_zero(),
+        (true, true) => false,
+        (false, true) => r * r > d * s * s,
+        (true, false) => d * s * s > r * r,
+    }
+}
+
+/// Runs the PQa algorithm on (p0 + √d) / q0, stopping at the first
+/// i >= 1 with Q_i = ±1, and returning (G_{i-1}, B_{i-1}). Returns None
+/// if the expansion becomes periodic without reaching Q_i = ±1.
+fn pqa_solution(p0: i64, q0: i64, d: u64) -> Option<(BigInt, BigInt)> {
+    let d_int = d as i128;
+    let sqrt_d = isqrt(d) as i128;
+    let (mut p, mut q) = (p0 as i128, q0 as i128);
+    let (mut g0, mut g1) = (BigInt::from(-p0), BigInt::from(q0));
+    let (mut b0, mut b1) = (BigInt::one(), BigInt::zero());
+
+    let mut seen = HashSet::new();
+    loop {
+        let a = surd_floor(p, q, sqrt_d);
+        (g0, g1) = (g1.clone(), a * g1 + g0);
+        (b0, b1) = (b1.clone(), a * b1 + b0);
+
+        p = a * q - p;
+        q = (d_int - p * p) / q;
+        if q == 1 || q == -1 {
+            return Some((g1, b1));
+        }
+        if !seen.insert((p, q)) {
+            return None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pell_fundamental() {
+        let small = |d| {
+            let (x, y) = pell_fundamental(d).unwrap();
+            (x.try_into().unwrap(), y.try_into().unwrap())
+        };
+        assert_eq!(small(2), (3_u64, 2_u64));
+        assert_eq!(small(13), (649, 180));
+        assert_eq!(small(61), (1766319049, 226153980));
+        assert!(pell_fundamental(9).is_none());
+
+        // Compare with a brute force search for small D.
+        for d in 2..40 {
+            if isqrt(d) * isqrt(d) == d {
+                continue;
+            }
+            let y = (1..).find(|y| is_square(d * y * y + 1)).unwrap();
+            let x = isqrt(d * y * y + 1);
+            assert_eq!(small(d), (x, y), "D = {}", d);
+        }
+    }
+
+    #[test]
+    fn test_negative_pell() {
+        let (x, y) = negative_pell_fundamental(13).unwrap();
+        assert_eq!((x, y), (BigInt::from(18), BigInt::from(5)));
+        assert!(negative_pell_fundamental(3).is_none());
+
+        for d in 2..100 {
+            let has_solution = negative_pell_fundamental(d).is_some();
+            let period = sqrt_cf(d).period.len();
+            assert_eq!(has_solution, period % 2 == 1, "D = {}", d);
+        }
+    }
+
+    #[test]
+    fn test_pell_solutions_brute_force() {
+        let limit = 2000;
+        for d in 2..16_i64 {
+            if is_square(d as u64) {
+                continue;
+            }
+            for n in -30..30 {
+                if n == 0 {
+                    continue;
+                }
+                let mut expected = vec![];
+                for x in 0..limit {
+                    let dy2 = x * x - n;
+                    if dy2 >= 0 && dy2 % d == 0 && is_square((dy2 / d) as u64) {
+                        expected.push((x, isqrt(dy2 / d)));
+                    }
+                }
+                let actual = pell_solutions(d as u64, n)
+                    .map(|(x, y)| (i64::try_from(x).unwrap(), i64::try_from(y).unwrap()))
+                    .take_while(|&(x, _)| x < limit)
+                    .collect::<Vec<_>>();
+                assert_eq!(actual, expected, "x² - {}y² = {}", d, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_big_solutions() {
+        // Each solution really does solve the equation, well past the
+        // range of an i64.
+        let d = BigInt::from(991);
+        for (x, y) in pell_solutions(991, 1).take(5) {
+            assert_eq!(&x * &x - &d * &y * &y, BigInt::one());
+        }
+        let d = BigInt::from(1021);
+        for (x, y) in pell_solutions(1021, -4).take(5) {
+            assert_eq!(&x * &x - &d * &y * &y, BigInt::from(-4));
+        }
+    }
+
+    fn is_square(n: u64) -> bool {
+        let r = isqrt(n);
+        r * r == n
+    }
+}
@@ -0,0 +1,296 @@
+use crate::binomial;
+
+/// Rearranges xs into the next permutation in lexicographic order, and
+/// returns true. If xs is already the last one, it goes back round to
+/// the first, sorted, and returns false.
+///
+/// Equal elements are never swapped with each other, so starting from
+/// sorted order this goes through each distinct arrangement exactly once.
+///
+/// ```
+/// let mut xs = [1, 2, 3];
+/// assert!(euler::next_permutation(&mut xs));
+/// assert_eq!(xs, [1, 3, 2]);
+///
+/// let mut xs = [3, 2, 1];
+/// assert!(!euler::next_permutation(&mut xs));
+/// assert_eq!(xs, [1, 2, 3]);
+/// ```
+pub fn next_permutation<T: Ord>(xs: &mut [T]) -> bool {
+    // Find the longest non-increasing suffix. The element before it is
+    // the one to increase, by swapping it with the smallest bigger one
+    // in the suffix; then the suffix starts again from its smallest.
+    let Some(i) = (1..xs.len()).rev().find(|&i| xs[i - 1] < xs[i]) else {
+        xs.reverse();
+        return false;
+    };
+    let j = (i..xs.len()).rev().find(|&j| xs[i - 1] < xs[j]).unwrap();
+    xs.swap(i - 1, j);
+    xs[i..].reverse();
+    true
+}
+
+/// Rearranges xs into the previous permutation in lexicographic order,
+/// and returns true, or goes round to the last one and returns false.
+///
+/// ```
+/// let mut xs = [1, 3, 2];
+/// assert!(euler::prev_permutation(&mut xs));
+/// assert_eq!(xs, [1, 2, 3]);
+/// assert!(!euler::prev_permutation(&mut xs));
+/// assert_eq!(xs, [3, 2, 1]);
+/// ```
+pub fn prev_permutation<T: Ord>(xs: &mut [T]) -> bool {
+    // The mirror image of next_permutation.
+    let Some(i) = (1..xs.len()).rev().find(|&i| xs[i - 1] > xs[i]) else {
+        xs.reverse();
+        return false;
+    };
+    let j = (i..xs.len()).rev().find(|&j| xs[i - 1] > xs[j]).unwrap();
+    xs.swap(i - 1, j);
+    xs[i..].reverse();
+    true
+}
+
+/// The position of a permutation of distinct elements among all the
+/// permutations of them in lexicographic order, counting from 0.
+///
+/// Each element contributes the number of smaller elements after it,
+/// times the factorial of the number of places after it: the rank in
+/// the factorial number system.
+///
+/// ```
+/// assert_eq!(euler::permutation_rank(&[0, 1, 2]), 0);
+/// assert_eq!(euler::permutation_rank(&[2, 1, 0]), 5);
+/// assert_eq!(euler::permutation_rank(&"dcba".chars().collect::<Vec<_>>()), 23);
+/// ```
+///
+/// Panics if the rank doesn't fit in a u128, which can only happen for
+/// more than 34 elements, or if there are more than 35, since then the
+/// place values don't fit either.
+pub fn permutation_rank<T: Ord>(xs: &[T]) -> u128 {
+    let mut rank = 0_u128;
+    // place_value is (n - 1 - i)!, built up from the right.
+    let mut place_value = 1_u128;
+    for (i, x) in xs.iter().enumerate().rev() {
+        let smaller_after = xs[i + 1..].iter().filter(|&y| y < x).count() as u128;
+        rank = place_value
+            .checked_mul(smaller_after)
+            .and_then(|r| r.checked_add(rank))
+            .expect("Permutation rank is too big for a u128");
+        if i > 0 {
+            place_value = place_value
+                .checked_mul((xs.len() - i) as u128)
+                .expect("Permutation rank is too big for a u128");
+        }
+    }
+    rank
+}
+
+/// The permutation of the given distinct items at position `rank` in
+/// lexicographic order, counting from 0. The items can come in any
+/// order.
+///
+/// ```
+/// // Problem 24: the millionth permutation of the digits 0 to 9.
+/// let digits = (0..10).collect::<Vec<u32>>();
+/// let millionth: u64 = euler::from_digits(euler::permutation_unrank(&digits, 999_999), 10);
+/// assert_eq!(millionth, 2783915460);
+/// ```
+///
+/// Panics if the rank is too big, i.e. not less than n!.
+pub fn permutation_unrank<T: Ord + Clone>(items: &[T], rank: u128) -> Vec<T> {
+    let mut left = items.to_vec();
+    left.sort();
+    // The factorial digits of the rank, most significant first.
+    let mut digits = vec![0; left.len()];
+    let mut rest = rank;
+    for (place, digit) in digits.iter_mut().rev().enumerate() {
+        let base = place as u128 + 1;
+        *digit = (rest % base) as usize;
+        rest /= base;
+    }
+    if rest > 0 {
+        panic!("Rank {} is too big for {} items", rank, items.len());
+    }
+    digits.into_iter().map(|d| left.remove(d)).collect()
+}
+
+/// The position of a k-combination of 0..n among all of them in
+/// lexicographic order, counting from 0. The combination should be in
+/// increasing order.
+///
+/// The combinations that come after c0 < c1 < ... are those with a
+/// bigger c0, or the same c0 and a bigger c1, and so on; there are
+/// C(n - 1 - c_i, k - i) of each, so this is C(n, k) - 1 minus those.
+///
+/// ```
+/// // 01 02 03 12 13 23
+/// assert_eq!(euler::combination_rank(&[0, 1], 4), 0);
+/// assert_eq!(euler::combination_rank(&[1, 3], 4), 4);
+/// assert_eq!(euler::combination_rank(&[2, 3], 4), 5);
+/// ```
+///
+/// Panics if the combination isn't in increasing order, or has elements
+/// that aren't less than n.
+pub fn combination_rank(combination: &[u64], n: u64) -> u128 {
+    if combination.windows(2).any(|w| w[0] >= w[1]) || combination.iter().any(|&c| c >= n) {
+        panic!("{:?} isn't a combination of 0..{}", combination, n);
+    }
+    let k = combination.len() as u64;
+    let after = combination
+        .iter()
+        .zip(0..)
+        .map(|(&c, i)| binomial(n - 1 - c, k - i))
+        .sum::<u128>();
+    binomial(n, k) - 1 - after
+}
+
+/// The k-combination of 0..n at position `rank` in lexicographic order,
+/// counting from 0, in increasing order.
+///
+/// ```
+/// assert_eq!(euler::combination_unrank(4, 2, 4), [1, 3]);
+/// assert_eq!(euler::combination_unrank(100, 3, 0), [0, 1, 2]);
+/// ```
+///
+/// Panics if the rank isn't less than C(n, k).
+pub fn combination_unrank(n: u64, k: u64, rank: u128) -> Vec<u64> {
+    if rank >= binomial(n, k) {
+        panic!("Rank {} is too big for C({}, {})", rank, n, k);
+    }
+    let mut rest = rank;
+    let mut combination = Vec::with_capacity(k as usize);
+    let mut next = 0;
+    for i in 0..k {
+        // Skip past all the combinations that start with a smaller value.
+        loop {
+            let starting_here = binomial(n - 1 - next, k - 1 - i);
+            if rest < starting_here {
+                break;
+            }
+            rest -= starting_here;
+            next += 1;
+        }
+        combination.push(next);
+        next += 1;
+    }
+    combination
+}
+
+/// Iterates over the distinct permutations of the items in lexicographic
+/// order. Unlike itertools' permutations, repeated items don't lead to
+/// repeated permutations.
+///
+/// ```
+/// let words = euler::multiset_permutations("aab".chars())
+///     .map(|p| p.into_iter().collect::<String>())
+///     .collect::<Vec<_>>();
+/// assert_eq!(words, ["aab", "aba", "baa"]);
+///
+/// // The ways of placing 3 repeated digits among 5 places.
+/// assert_eq!(euler::multiset_permutations([0, 0, 1, 1, 1]).count(), 10);
+/// ```
+pub fn multiset_permutations<T: Ord + Clone>(
+    items: impl IntoIterator<Item = T>,
+) -> MultisetPermutations<T> {
+    let mut next = items.into_iter().collect::<Vec<_>>();
+    next.sort();
+    MultisetPermutations { next: Some(next) }
+}
+
+pub struct MultisetPermutations<T> {
+    // None once we've gone round to the start again.
+    next: Option<Vec<T>>,
+}
+
+impl<T: Ord + Clone> Iterator for MultisetPermutations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        let current = self.next.take()?;
+        let mut next = current.clone();
+        if next_permutation(&mut next) {
+            self.next = Some(next);
+        }
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_matches_itertools() {
+        for n in 0..7 {
+            let all = (0..n).permutations(n).collect::<Vec<_>>();
+            assert_eq!(multiset_permutations(0..n).collect::<Vec<_>>(), all);
+            for (rank, p) in all.iter().enumerate() {
+                assert_eq!(permutation_rank(p), rank as u128);
+                assert_eq!(&permutation_unrank(&all[0], rank as u128), p);
+                let mut prev = p.clone();
+                assert_eq!(prev_permutation(&mut prev), rank > 0);
+                assert_eq!(&prev, &all[(rank + all.len() - 1) % all.len()]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_multisets() {
+        let items = [3, 1, 3, 2, 1, 3];
+        let all = multiset_permutations(items).collect::<Vec<_>>();
+        // 6! / 3! 2! 1!
+        assert_eq!(all.len(), 60);
+        assert!(all.windows(2).all(|w| w[0] < w[1]));
+        let expected = items.iter().permutations(6).unique().count();
+        assert_eq!(all.len(), expected);
+        assert_eq!(multiset_permutations(Vec::<u8>::new()).count(), 1);
+    }
+
+    #[test]
+    fn test_combinations() {
+        for n in 0..10 {
+            for k in 0..=n {
+                let all = (0..n).combinations(k as usize).collect::<Vec<_>>();
+                for (rank, c) in all.iter().enumerate() {
+                    assert_eq!(combination_rank(c, n), rank as u128);
+                    assert_eq!(&combination_unrank(n, k, rank as u128), c);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_big() {
+        let letters = ('a'..='z').collect::<Vec<_>>();
+        let last = letters.iter().rev().copied().collect::<Vec<_>>();
+        let count = (1..=26_u128).product::<u128>();
+        assert_eq!(permutation_rank(&last), count - 1);
+        assert_eq!(permutation_unrank(&letters, count - 1), last);
+
+        // 35 elements only fit when the first one is small.
+        let mut xs = (0..35).collect::<Vec<_>>();
+        xs.swap(0, 1);
+        assert_eq!(permutation_rank(&xs), (1..=34_u128).product::<u128>());
+
+        let c = combination_unrank(60, 30, 12345678901234567);
+        assert_eq!(combination_rank(&c, 60), 12345678901234567);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rank_too_big() {
+        permutation_unrank(&[1, 2, 3], 6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rank_overflow() {
+        // 2 × 34! is more than u128::MAX.
+        let mut xs = (0..35).collect::<Vec<_>>();
+        xs.swap(0, 2);
+        permutation_rank(&xs);
+    }
+}
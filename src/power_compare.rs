@@ -0,0 +1,200 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use num_bigint::BigInt;
+
+use crate::iroot;
+use crate::Fixed;
+
+/// A power base^exp, which might be far too big to write out, but can
+/// still be compared exactly with other powers.
+///
+/// ```
+/// use euler::Power;
+///
+/// assert!(Power::new(2, 1000) < Power::new(3, 631));
+/// assert_eq!(Power::new(8, 2), Power::new(4, 3));
+///
+/// // These two are equal to 28 significant figures, which is far more
+/// // than f64 can see.
+/// assert!(Power::new(23, 547198034243) < Power::new(31, 499633856846));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Power {
+    pub base: u64,
+    pub exp: u64,
+}
+
+impl Power {
+    pub fn new(base: u64, exp: u64) -> Self {
+        Self { base, exp }
+    }
+
+    /// Writes out the power in full. Only sensible for smallish ones.
+    pub fn to_bigint(&self) -> BigInt {
+        let exp = u32::try_from(self.exp).expect("Power is too big to write out");
+        BigInt::from(self.base).pow(exp)
+    }
+}
+
+impl PartialEq for Power {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Power {}
+
+impl PartialOrd for Power {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Power {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_powers(self.base, self.exp, other.base, other.exp)
+    }
+}
+
+impl fmt::Display for Power {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}^{}", self.base, self.exp)
+    }
+}
+
+/// Compares a^b with c^d exactly. (0^0 counts as 1.)
+///
+/// Comparing b ln(a) with d ln(c) is the obvious approach, but when the
+/// two are close, f64 rounding can tie them or put them the wrong way
+/// round. So this goes in stages, each only when the last can't decide:
+///
+/// 1. f64 logs, with a bound on their rounding error.
+/// 2. Exact ties. If a and c are powers of the same number r, comparing
+///    the exponents of r is enough; that's also the only way the powers
+///    can be equal.
+/// 3. Logs from `Fixed`, with the same sort of bound, starting at 50
+///    digits and doubling until the difference is clear. Since the
+///    powers aren't equal, their logs aren't either, so this always
+///    finishes; 50 digits already separate powers that agree to over 30
+///    significant figures.
+pub fn compare_powers(a: u64, b: u64, c: u64, d: u64) -> Ordering {
+    // Get 0 and 1 out of the way, so that both bases are at least 2.
+    let (size1, size2) = (trivial_size(a, b), trivial_size(c, d));
+    if size1.is_some() || size2.is_some() {
+        return size1.unwrap_or(2).cmp(&size2.unwrap_or(2));
+    }
+
+    let (log1, log2) = (b as f64 * (a as f64).ln(), d as f64 * (c as f64).ln());
+    // Each log is good to a few ulps, and so is each product.
+    let error = 8.0 * f64::EPSILON * (log1 + log2);
+    if (log1 - log2).abs() > error {
+        return log1.partial_cmp(&log2).unwrap();
+    }
+
+    let (r1, m1) = perfect_power_root(a);
+    let (r2, m2) = perfect_power_root(c);
+    if r1 == r2 {
+        return (m1 as u128 * b as u128).cmp(&(m2 as u128 * d as u128));
+    }
+
+    let mut digits = 50;
+    loop {
+        if let Some(ordering) = compare_logs(a, b, c, d, digits) {
+            return ordering;
+        }
+        digits *= 2;
+    }
+}
+
+// Compares b ln(a) with d ln(c) to the given number of digits, if that's
+// enough to tell them apart.
+fn compare_logs(a: u64, b: u64, c: u64, d: u64, digits: u32) -> Option<Ordering> {
+    let log =
+        |base: u64, exp: u64| Fixed::from_int(base, digits).ln() * Fixed::from_int(exp, digits);
+    let diff = log(a, b) - log(c, d);
+    // Each ln(base) is within half a unit in the last place, so each
+    // product is out by at most (exp + 1) units.
+    let error = Fixed::from_ratio(
+        BigInt::from(b) + d + 2,
+        BigInt::from(10).pow(digits),
+        digits,
+    );
+    (diff.abs() > error).then(|| diff.cmp(&Fixed::zero(digits)))
+}
+
+// Sizes 0 and 1 for a power that works out as 0 or 1.
+fn trivial_size(base: u64, exp: u64) -> Option<u8> {
+    match (base, exp) {
+        (_, 0) | (1, _) => Some(1),
+        (0, _) => Some(0),
+        _ => None,
+    }
+}
+
+// Writes n ≥ 2 as r^m with m as big as possible, so that r isn't itself
+// a perfect power.
+fn perfect_power_root(n: u64) -> (u64, u32) {
+    for m in (2..64).rev() {
+        let r: u64 = iroot(n, m);
+        if r >= 2 && r.checked_pow(m) == Some(n) {
+            return (r, m);
+        }
+    }
+    (n, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_trivial() {
+        assert_eq!(compare_powers(0, 0, 1, 5), Ordering::Equal);
+        assert_eq!(compare_powers(0, 3, 7, 0), Ordering::Less);
+        assert_eq!(compare_powers(2, 1, 1, 1000), Ordering::Greater);
+        assert_eq!(compare_powers(0, 1, 0, 1000), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_ties() {
+        assert_eq!(Power::new(2, 6), Power::new(4, 3));
+        assert_eq!(Power::new(8, 2), Power::new(64, 1));
+        assert_eq!(Power::new(1 << 32, 1 << 40), Power::new(1 << 16, 1 << 41));
+        assert!(Power::new(1 << 32, 1 << 40) < Power::new(1 << 16, (1 << 41) + 1));
+        assert_eq!(perfect_power_root(1 << 63), (2, 63));
+        assert_eq!(perfect_power_root(36), (6, 2));
+        assert_eq!(perfect_power_root(u64::MAX), (u64::MAX, 1));
+    }
+
+    #[test]
+    fn test_near_ties() {
+        // Found with continued fractions of ln(c) / ln(a).
+        let cases = [
+            (23, 547198034243, 31, 499633856846, Ordering::Less),
+            (5, 807904723887, 29, 386147388857, Ordering::Greater),
+            (21, 928447592031, 22, 914474507821, Ordering::Less),
+        ];
+        for (a, b, c, d, expected) in cases {
+            assert_eq!(compare_powers(a, b, c, d), expected);
+            assert_eq!(compare_powers(c, d, a, b), expected.reverse());
+        }
+    }
+
+    #[test]
+    fn test_more_digits() {
+        // Too close for 20 digits, but 40 is plenty.
+        let (a, b, c, d) = (23, 547198034243, 31, 499633856846);
+        assert_eq!(compare_logs(a, b, c, d, 20), None);
+        assert_eq!(compare_logs(a, b, c, d, 40), Some(Ordering::Less));
+    }
+
+    proptest! {
+        #[test]
+        fn matches_bigint(a in 0..300_u64, b in 0..300_u64, c in 0..300_u64, d in 0..300_u64) {
+            let expected = Power::new(a, b).to_bigint().cmp(&Power::new(c, d).to_bigint());
+            prop_assert_eq!(compare_powers(a, b, c, d), expected);
+        }
+    }
+}
@@ -0,0 +1,397 @@
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::Add;
+use std::ops::Div;
+use std::ops::Mul;
+use std::ops::Neg;
+use std::ops::Sub;
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::One;
+use num_traits::Signed;
+use num_traits::ToPrimitive;
+use num_traits::Zero;
+
+/// An exact rational number.
+///
+/// Most of the time, the numerator and denominator are i64s, and arithmetic
+/// is done in i128 so that overflow can't happen unnoticed. If a result
+/// doesn't fit back into an i64, it's kept as a BigInt fraction instead,
+/// and goes back to i64 if it gets small enough again.
+///
+/// ```
+/// use euler::Rational;
+///
+/// let third = Rational::new(1, 3);
+/// assert_eq!(third.clone() * Rational::from(3), Rational::from(1));
+/// assert_eq!((third + Rational::new(1, 6)).to_string(), "1/2");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Rational(Repr);
+
+// Always in lowest terms with a positive denominator, and only Big if
+// it won't fit in Small. That means that derived equality and hashing
+// work.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Repr {
+    Small(i64, i64),
+    Big(BigInt, BigInt),
+}
+
+impl Rational {
+    /// Creates the fraction num/den, reducing it to lowest terms.
+    ///
+    /// Panics if den is zero.
+    pub fn new(num: i64, den: i64) -> Self {
+        Self::from_i128(num as i128, den as i128)
+    }
+
+    /// Creates the fraction num/den from BigInts.
+    ///
+    /// Panics if den is zero.
+    pub fn from_big(num: BigInt, den: BigInt) -> Self {
+        if den.is_zero() {
+            panic!("Rational with zero denominator");
+        }
+        let g = num.gcd(&den);
+        let (mut num, mut den) = (num / &g, den / g);
+        if den.is_negative() {
+            num = -num;
+            den = -den;
+        }
+        match (num.to_i64(), den.to_i64()) {
+            (Some(n), Some(d)) => Self(Repr::Small(n, d)),
+            _ => Self(Repr::Big(num, den)),
+        }
+    }
+
+    fn from_i128(num: i128, den: i128) -> Self {
+        if den == 0 {
+            panic!("Rational with zero denominator");
+        }
+        let g = num.gcd(&den);
+        let (mut num, mut den) = (num / g, den / g);
+        if den < 0 {
+            num = -num;
+            den = -den;
+        }
+        match (i64::try_from(num), i64::try_from(den)) {
+            (Ok(n), Ok(d)) => Self(Repr::Small(n, d)),
+            _ => Self(Repr::Big(num.into(), den.into())),
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self(Repr::Small(0, 1))
+    }
+
+    pub fn one() -> Self {
+        Self(Repr::Small(1, 1))
+    }
+
+    pub fn numer(&self) -> BigInt {
+        match &self.0 {
+            Repr::Small(n, _) => BigInt::from(*n),
+            Repr::Big(n, _) => n.clone(),
+        }
+    }
+
+    pub fn denom(&self) -> BigInt {
+        match &self.0 {
+            Repr::Small(_, d) => BigInt::from(*d),
+            Repr::Big(_, d) => d.clone(),
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        matches!(self.0, Repr::Small(0, _))
+    }
+
+    pub fn is_negative(&self) -> bool {
+        match &self.0 {
+            Repr::Small(n, _) => *n < 0,
+            Repr::Big(n, _) => n.is_negative(),
+        }
+    }
+
+    pub fn is_integer(&self) -> bool {
+        match &self.0 {
+            Repr::Small(_, d) => *d == 1,
+            Repr::Big(_, d) => d.is_one(),
+        }
+    }
+
+    /// Returns the value as an i64, if it's a whole number that fits.
+    pub fn to_i64(&self) -> Option<i64> {
+        match self.0 {
+            Repr::Small(n, 1) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Converts to the nearest f64, for display or rough comparisons.
+    pub fn to_f64(&self) -> f64 {
+        match &self.0 {
+            Repr::Small(n, d) => *n as f64 / *d as f64,
+            Repr::Big(n, d) => {
+                // Scale down first so that the division doesn't overflow.
+                let shift = n.bits().max(d.bits()).saturating_sub(1000);
+                let n = (n >> shift).to_f64().unwrap();
+                let d = (d >> shift).to_f64().unwrap();
+                n / d
+            }
+        }
+    }
+
+    /// Returns 1/self, or None if self is zero.
+    pub fn recip(&self) -> Option<Self> {
+        match &self.0 {
+            Repr::Small(0, _) => None,
+            Repr::Small(n, d) => Some(Self::new(*d, *n)),
+            Repr::Big(n, d) => Some(Self::from_big(d.clone(), n.clone())),
+        }
+    }
+
+    /// Divides, returning None instead of panicking on division by zero.
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        Some(self * &rhs.recip()?)
+    }
+
+    /// Raises to an integer power, which may be negative. Returns None
+    /// if that means dividing by zero.
+    pub fn pow(&self, exp: i32) -> Option<Self> {
+        let base = if exp < 0 { self.recip()? } else { self.clone() };
+        let exp = exp.unsigned_abs();
+        let num = num_traits::pow(base.numer(), exp as usize);
+        let den = num_traits::pow(base.denom(), exp as usize);
+        Some(Self::from_big(num, den))
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Self(Repr::Small(n, 1))
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(n: BigInt) -> Self {
+        Self::from_big(n, BigInt::one())
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer())
+        } else {
+            write!(f, "{}/{}", self.numer(), self.denom())
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Denominators are positive, so cross multiplication is safe.
+        match (&self.0, &other.0) {
+            (Repr::Small(a, b), Repr::Small(c, d)) => {
+                (*a as i128 * *d as i128).cmp(&(*c as i128 * *b as i128))
+            }
+            _ => (self.numer() * other.denom()).cmp(&(other.numer() * self.denom())),
+        }
+    }
+}
+
+impl<'a> Add<&'a Rational> for &'a Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Self) -> Rational {
+        if let (Repr::Small(a, b), Repr::Small(c, d)) = (&self.0, &rhs.0) {
+            let (a, b, c, d) = (*a as i128, *b as i128, *c as i128, *d as i128);
+            if let Some(num) = (a * d).checked_add(c * b) {
+                return Rational::from_i128(num, b * d);
+            }
+        }
+        let (a, b, c, d) = (self.numer(), self.denom(), rhs.numer(), rhs.denom());
+        Rational::from_big(a * &d + c * &b, b * d)
+    }
+}
+
+impl<'a> Sub<&'a Rational> for &'a Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Self) -> Rational {
+        self + &-rhs
+    }
+}
+
+impl<'a> Mul<&'a Rational> for &'a Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Self) -> Rational {
+        match (&self.0, &rhs.0) {
+            (Repr::Small(a, b), Repr::Small(c, d)) => {
+                let (a, b, c, d) = (*a as i128, *b as i128, *c as i128, *d as i128);
+                Rational::from_i128(a * c, b * d)
+            }
+            _ => Rational::from_big(self.numer() * rhs.numer(), self.denom() * rhs.denom()),
+        }
+    }
+}
+
+impl<'a> Div<&'a Rational> for &'a Rational {
+    type Output = Rational;
+
+    /// Panics on division by zero; use `checked_div` to avoid this.
+    fn div(self, rhs: Self) -> Rational {
+        self.checked_div(rhs).expect("Rational division by zero")
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        match &self.0 {
+            Repr::Small(n, d) => Rational::from_i128(-(*n as i128), *d as i128),
+            Repr::Big(n, d) => Rational::from_big(-n, d.clone()),
+        }
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        -&self
+    }
+}
+
+// The operators on owned values just borrow.
+macro_rules! forward_binop {
+    ($trait:ident, $method:ident) => {
+        impl $trait for Rational {
+            type Output = Rational;
+
+            fn $method(self, rhs: Self) -> Rational {
+                (&self).$method(&rhs)
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add);
+forward_binop!(Sub, sub);
+forward_binop!(Mul, mul);
+forward_binop!(Div, div);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn r(num: i64, den: i64) -> Rational {
+        Rational::new(num, den)
+    }
+
+    #[test]
+    fn test_normalized() {
+        assert_eq!(r(2, 4), r(1, 2));
+        assert_eq!(r(-2, -4), r(1, 2));
+        assert_eq!(r(2, -4), r(-1, 2));
+        assert_eq!(r(0, -5), Rational::zero());
+        assert_eq!(r(2, -4).to_string(), "-1/2");
+        assert_eq!(r(6, 3).to_string(), "2");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_denominator() {
+        r(1, 0);
+    }
+
+    #[test]
+    fn test_exact() {
+        // In floating point, 1/3 * 3 is fine but (1/3 + 1/3 + 1/3) - 1
+        // and friends often aren't.
+        let third = r(1, 3);
+        assert_eq!(&third * &r(3, 1), Rational::one());
+        assert_eq!(&(&third + &third) + &third, Rational::one());
+        assert_eq!(r(1, 10) + r(2, 10), r(3, 10));
+        assert_eq!(r(8, 1) / (r(3, 1) - r(8, 3)), r(24, 1));
+    }
+
+    #[test]
+    fn test_overflow_to_big() {
+        let max = Rational::from(i64::MAX);
+        let sum = &max + &max;
+        assert_eq!(sum.numer(), BigInt::from(i64::MAX) * 2);
+        assert_eq!(sum.to_i64(), None);
+        assert!(sum.is_integer());
+
+        // ...and back again.
+        assert_eq!(&sum - &max, max);
+        assert_eq!(&sum / &r(2, 1), max);
+
+        let min = Rational::from(i64::MIN);
+        assert_eq!(-&min, &max + &Rational::one());
+        assert_eq!(-(-&min), min);
+
+        let tiny = r(1, i64::MAX);
+        let tinier = &tiny * &tiny;
+        assert_eq!(tinier.denom(), BigInt::from(i64::MAX) * i64::MAX);
+        assert!(tinier > Rational::zero());
+        assert!(tinier < tiny);
+    }
+
+    #[test]
+    fn test_pow() {
+        assert_eq!(r(2, 3).pow(3), Some(r(8, 27)));
+        assert_eq!(r(2, 3).pow(-2), Some(r(9, 4)));
+        assert_eq!(r(5, 7).pow(0), Some(Rational::one()));
+        assert_eq!(Rational::zero().pow(-1), None);
+        assert_eq!(r(2, 1).pow(100).unwrap().numer(), BigInt::one() << 100);
+    }
+
+    #[test]
+    fn test_checked_div() {
+        assert_eq!(r(1, 2).checked_div(&Rational::zero()), None);
+        assert_eq!(r(1, 2).checked_div(&r(1, 4)), Some(r(2, 1)));
+    }
+
+    proptest! {
+        #[test]
+        fn field_laws(a: i64, b in 1..i64::MAX, c: i64, d in 1..i64::MAX) {
+            let x = r(a, b);
+            let y = r(c, d);
+            prop_assert_eq!(&(&x + &y) - &y, x.clone());
+            prop_assert_eq!(&x + &y, &y + &x);
+            prop_assert_eq!(&x * &y, &y * &x);
+            if !y.is_zero() {
+                prop_assert_eq!(&(&x * &y) / &y, x.clone());
+            }
+        }
+
+        #[test]
+        fn ordering_matches_f64(a in -1000..1000_i64, b in 1..1000_i64, c in -1000..1000_i64, d in 1..1000_i64) {
+            let lhs = a as f64 / b as f64;
+            let rhs = c as f64 / d as f64;
+            if a * d != b * c {
+                prop_assert_eq!(r(a, b) < r(c, d), lhs < rhs);
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+use crate::mod_inverse;
+use crate::mul_mod;
+
+/// A linear recurrence mod m,
+///
+///   a(n) = c[0] a(n - 1) + c[1] a(n - 2) + ... + c[k - 1] a(n - k),
+///
+/// together with its first k terms.
+///
+/// ```
+/// use euler::Recurrence;
+///
+/// let fibonacci = Recurrence::new(vec![1, 1], vec![0, 1], 1_000_000_007);
+/// assert_eq!(fibonacci.nth(10), 55);
+/// assert_eq!(fibonacci.nth(1_000_000_000_000_000_000), 209783453);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recurrence {
+    coefficients: Vec<u64>,
+    initial: Vec<u64>,
+    modulus: u64,
+}
+
+impl Recurrence {
+    /// Panics if there isn't one initial term per coefficient, or if the
+    /// modulus is 0.
+    pub fn new(coefficients: Vec<u64>, initial: Vec<u64>, modulus: u64) -> Self {
+        if modulus == 0 {
+            panic!("Recurrence mod 0");
+        }
+        if coefficients.len() != initial.len() {
+            panic!(
+                "{} coefficients but {} initial terms",
+                coefficients.len(),
+                initial.len()
+            );
+        }
+        let reduce = |v: Vec<u64>| v.into_iter().map(|x| x % modulus).collect();
+        Self {
+            coefficients: reduce(coefficients),
+            initial: reduce(initial),
+            modulus,
+        }
+    }
+
+    /// Finds the shortest recurrence mod the prime p that produces the
+    /// given terms, with `berlekamp_massey`.
+    pub fn find(terms: &[u64], p: u64) -> Self {
+        let coefficients = berlekamp_massey(terms, p);
+        let initial = terms[..coefficients.len()].to_vec();
+        Self::new(coefficients, initial, p)
+    }
+
+    pub fn order(&self) -> usize {
+        self.coefficients.len()
+    }
+
+    pub fn coefficients(&self) -> &[u64] {
+        &self.coefficients
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.modulus
+    }
+
+    /// Calculates a(n), counting from a(0), in O(k² log n) steps.
+    ///
+    /// This is Kitamasa's method. Shifting the sequence along by one is
+    /// multiplying by x modulo the characteristic polynomial
+    /// x^k - c[0] x^(k-1) - ... - c[k-1], so x^n mod that polynomial
+    /// writes a(n) as a combination of a(0), ..., a(k - 1).
+    pub fn nth(&self, n: u64) -> u64 {
+        let k = self.order();
+        if n < k as u64 {
+            return self.initial[n as usize];
+        }
+        let mut power = vec![1 % self.modulus];
+        for bit in (0..64 - n.leading_zeros()).rev() {
+            power = self.multiply(&power, &power);
+            if n >> bit & 1 == 1 {
+                power.insert(0, 0);
+                self.reduce(&mut power);
+            }
+        }
+        power.iter().zip(&self.initial).fold(0, |sum, (&r, &a)| {
+            self.add(sum, mul_mod(r, a, self.modulus))
+        })
+    }
+
+    fn add(&self, a: u64, b: u64) -> u64 {
+        ((a as u128 + b as u128) % self.modulus as u128) as u64
+    }
+
+    // Multiplies two polynomials mod the characteristic polynomial.
+    fn multiply(&self, p: &[u64], q: &[u64]) -> Vec<u64> {
+        let mut product = vec![0; (p.len() + q.len()).saturating_sub(1)];
+        for (i, &a) in p.iter().enumerate() {
+            for (j, &b) in q.iter().enumerate() {
+                product[i + j] = self.add(product[i + j], mul_mod(a, b, self.modulus));
+            }
+        }
+        self.reduce(&mut product);
+        product
+    }
+
+    // Brings the degree below k, using x^k = c[0] x^(k-1) + ... + c[k-1].
+    fn reduce(&self, poly: &mut Vec<u64>) {
+        let k = self.order();
+        while poly.len() > k {
+            let top = poly.pop().unwrap();
+            let d = poly.len();
+            for (j, &c) in self.coefficients.iter().enumerate() {
+                poly[d - 1 - j] = self.add(poly[d - 1 - j], mul_mod(top, c, self.modulus));
+            }
+        }
+    }
+}
+
+/// Finds the coefficients of the shortest linear recurrence mod the
+/// prime p that produces the given terms, in the same order as
+/// `Recurrence`: a(n) = c[0] a(n - 1) + c[1] a(n - 2) + ...
+///
+/// A recurrence of order k is only pinned down by 2k terms, so give it
+/// at least twice as many terms as you expect the order to be; with too
+/// few, this still returns a recurrence, just not the right one.
+///
+/// ```
+/// // Powers of 2 plus powers of 3: a(n) = 5a(n - 1) - 6a(n - 2).
+/// let p = 1_000_000_007;
+/// let terms = (0..10).map(|n| 2_u64.pow(n) + 3_u64.pow(n)).collect::<Vec<_>>();
+/// assert_eq!(euler::berlekamp_massey(&terms, p), [5, p - 6]);
+/// ```
+///
+/// Panics if p isn't prime, or at least if that leads to dividing by
+/// something that has no inverse.
+pub fn berlekamp_massey(terms: &[u64], p: u64) -> Vec<u64> {
+    let sub = |a: u64, b: u64| ((a as u128 + p as u128 - b as u128) % p as u128) as u64;
+    // The connection polynomial 1 - c[0] x - c[1] x² - ..., and the one
+    // from before the last time its length changed.
+    let mut current = vec![1_u64];
+    let mut previous = vec![1_u64];
+    let mut length = 0;
+    let mut previous_discrepancy = 1;
+    let mut shift = 1;
+    for n in 0..terms.len() {
+        let discrepancy = (0..=length).fold(0, |sum, i| {
+            let term = mul_mod(current.get(i).copied().unwrap_or(0), terms[n - i] % p, p);
+            ((sum as u128 + term as u128) % p as u128) as u64
+        });
+        if discrepancy == 0 {
+            shift += 1;
+            continue;
+        }
+        let inverse = mod_inverse(previous_discrepancy, p).expect("Modulus must be prime");
+        let scale = mul_mod(discrepancy, inverse, p);
+        let before = current.clone();
+        if current.len() < previous.len() + shift {
+            current.resize(previous.len() + shift, 0);
+        }
+        for (i, &b) in previous.iter().enumerate() {
+            current[i + shift] = sub(current[i + shift], mul_mod(scale, b, p));
+        }
+        if 2 * length <= n {
+            length = n + 1 - length;
+            previous = before;
+            previous_discrepancy = discrepancy;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+    }
+    current.resize(length + 1, 0);
+    current[1..].iter().map(|&c| sub(0, c)).collect()
+}
+
+/// Guesses the nth term (counting from 0) of a sequence mod the prime
+/// p, from its first few terms, by assuming it follows a linear
+/// recurrence. This is handy for counting problems where brute force
+/// gives the start of the answer sequence, but not the term asked for.
+///
+/// ```
+/// // Tilings of a 3 × 2n board with dominoes: 1, 3, 11, 41, 153, ...
+/// let mut tilings = vec![1_u64, 3];
+/// for n in 2..10 {
+///     tilings.push(4 * tilings[n - 1] - tilings[n - 2]);
+/// }
+/// let p = 1_000_000_007;
+/// assert_eq!(euler::extrapolate(&tilings, 5, p), 571);
+/// assert_eq!(euler::extrapolate(&tilings, 10_u64.pow(18), p), 107537747);
+/// ```
+pub fn extrapolate(terms: &[u64], n: u64, p: u64) -> u64 {
+    Recurrence::find(terms, p).nth(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fib_mod;
+
+    const P: u64 = 1_000_000_007;
+
+    #[test]
+    fn test_matches_fibonacci() {
+        for m in [1, 2, 10, 1_000_000_007, u64::MAX - 58] {
+            let fibonacci = Recurrence::new(vec![1, 1], vec![0, 1], m);
+            for n in (0..200).chain([12345, 1 << 40, u64::MAX]) {
+                assert_eq!(fibonacci.nth(n), fib_mod(n, m), "F({}) mod {}", n, m);
+            }
+        }
+    }
+
+    #[test]
+    fn test_nth_brute_force() {
+        let coefficients = vec![3, 0, 7, 1, 12];
+        let initial = vec![1, 2, 3, 4, 5];
+        let recurrence = Recurrence::new(coefficients.clone(), initial.clone(), 1009);
+        let mut terms = initial;
+        for n in 5..300 {
+            let next = (0..5)
+                .map(|i| coefficients[i] * terms[n - 1 - i])
+                .sum::<u64>()
+                % 1009;
+            terms.push(next);
+        }
+        for (n, &t) in terms.iter().enumerate() {
+            assert_eq!(recurrence.nth(n as u64), t, "a({})", n);
+        }
+    }
+
+    #[test]
+    fn test_small_orders() {
+        let zero = Recurrence::new(vec![], vec![], P);
+        assert_eq!(zero.nth(0), 0);
+        assert_eq!(zero.nth(100), 0);
+
+        let powers = Recurrence::new(vec![3], vec![1], P);
+        assert_eq!(powers.nth(20), 3_u64.pow(20) % P);
+    }
+
+    #[test]
+    fn test_find() {
+        let recurrence = Recurrence::new(vec![2, 0, P - 1, 5], vec![1, 1, 4, 9], P);
+        let terms = (0..20).map(|n| recurrence.nth(n)).collect::<Vec<_>>();
+        assert_eq!(Recurrence::find(&terms, P), recurrence);
+        assert_eq!(extrapolate(&terms, 1 << 50, P), recurrence.nth(1 << 50));
+
+        // Terms that start off irregular, then settle down.
+        let terms = [7, 0, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55];
+        let found = Recurrence::find(&terms, P);
+        for (n, &t) in terms.iter().enumerate() {
+            assert_eq!(found.nth(n as u64), t);
+        }
+
+        assert_eq!(berlekamp_massey(&[0, 0, 0, 0], P), []);
+        assert_eq!(berlekamp_massey(&[], P), []);
+    }
+
+    #[test]
+    fn test_polynomial() {
+        // Cubes follow a recurrence of order 4.
+        let cubes = (0..10_u64).map(|n| n.pow(3)).collect::<Vec<_>>();
+        assert_eq!(berlekamp_massey(&cubes, P), [4, P - 6, 4, P - 1]);
+        assert_eq!(extrapolate(&cubes, 1000, P), 1_000_000_000 % P);
+    }
+}
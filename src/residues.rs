@@ -0,0 +1,266 @@
+use fixedbitset::FixedBitSet;
+use num_integer::Integer;
+use num_traits::FromPrimitive;
+use num_traits::ToPrimitive;
+
+/// Computes the Jacobi symbol (a/n) for odd positive n.
+///
+/// The result is 0 if a and n share a factor, and otherwise ±1. When n
+/// is prime, this is the Legendre symbol, but for composite n a result
+/// of 1 does *not* mean that a is a square mod n: (2/15) = 1, for example,
+/// but 2 is not a quadratic residue mod 15.
+///
+/// Works for any integer type, including u64 and BigInt. The algorithm
+/// is the usual one based on quadratic reciprocity, so it never needs
+/// to factor n.
+///
+/// Panics if n is even or not positive.
+pub fn jacobi<T>(a: T, n: T) -> i8
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    if n <= T::zero() || n.is_even() {
+        panic!("Jacobi symbol needs an odd positive modulus");
+    }
+    let two = T::from_u8(2).unwrap();
+    let mut a = a.mod_floor(&n);
+    let mut n = n;
+    let mut result = 1;
+
+    while !a.is_zero() {
+        // (2/n) = -1 exactly when n = ±3 mod 8.
+        while a.is_even() {
+            a = a / two.clone();
+            if matches!(mod_small(&n, 8), 3 | 5) {
+                result = -result;
+            }
+        }
+        // Quadratic reciprocity: (a/n) = (n/a) unless both are 3 mod 4.
+        std::mem::swap(&mut a, &mut n);
+        if mod_small(&a, 4) == 3 && mod_small(&n, 4) == 3 {
+            result = -result;
+        }
+        a = a.mod_floor(&n);
+    }
+
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+/// Computes the Legendre symbol (a/p), which is 1 if a is a non-zero
+/// square mod p, -1 if it is not a square, and 0 if p divides a.
+///
+/// The modulus p must be an odd prime. This isn't checked: for composite
+/// p you get the Jacobi symbol instead, which is probably not what you
+/// wanted.
+pub fn legendre<T>(a: T, p: T) -> i8
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    jacobi(a, p)
+}
+
+/// Computes the Kronecker symbol (a/n), which extends the Jacobi symbol
+/// to all integers n, including even and negative ones.
+pub fn kronecker<T>(a: T, n: T) -> i8
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    let zero = T::zero();
+    if n.is_zero() {
+        // Checking for -1 this way also works for unsigned types, and
+        // can't overflow.
+        let is_unit = a.is_one() || (a < zero && (a.clone() + T::one()).is_zero());
+        return if is_unit { 1 } else { 0 };
+    }
+
+    // Negating n waits until the factors of 2 are gone, since -n might
+    // not fit when n is the most negative value of its type.
+    let negative = n < zero;
+    let mut result = if negative && a < zero { -1 } else { 1 };
+    let mut n = n;
+
+    let two = T::from_u8(2).unwrap();
+    while n.is_even() {
+        if a.is_even() {
+            return 0;
+        }
+        // (a/2) is 1 if a = ±1 mod 8, and -1 if a = ±3 mod 8.
+        if matches!(mod_small(&a, 8), 3 | 5) {
+            result = -result;
+        }
+        n = n / two.clone();
+    }
+    if negative {
+        n = zero - n;
+    }
+
+    result * jacobi(a, n)
+}
+
+/// Returns the set of quadratic residues mod n, i.e., the values of
+/// x² mod n for all x. The set always contains 0.
+///
+/// ```
+/// let squares = euler::quadratic_residues(10);
+/// assert_eq!(squares.ones().collect::<Vec<_>>(), [0, 1, 4, 5, 6, 9]);
+/// ```
+pub fn quadratic_residues(n: usize) -> FixedBitSet {
+    let mut residues = FixedBitSet::with_capacity(n);
+    // (x + 1)² = x² + 2x + 1, so we never need to multiply.
+    let mut square = 0;
+    for x in 0..n {
+        residues.insert(square);
+        square = (square + 2 * x + 1) % n;
+    }
+    residues
+}
+
+fn mod_small<T>(n: &T, m: u8) -> u8
+where
+    T: Integer + Clone + FromPrimitive + ToPrimitive,
+{
+    n.mod_floor(&T::from_u8(m).unwrap()).to_u8().unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::factors;
+    use num_bigint::BigInt;
+    use primal::Primes;
+
+    fn odd_primes(below: usize) -> Vec<i64> {
+        Primes::all()
+            .skip(1)
+            .take_while(|&p| p < below)
+            .map(|p| p as i64)
+            .collect()
+    }
+
+    #[test]
+    fn test_legendre_brute_force() {
+        for p in odd_primes(100) {
+            let squares = quadratic_residues(p as usize);
+            for a in -2 * p..2 * p {
+                let expected = if a % p == 0 {
+                    0
+                } else if squares.contains(a.rem_euclid(p) as usize) {
+                    1
+                } else {
+                    -1
+                };
+                assert_eq!(legendre(a, p), expected, "({}/{})", a, p);
+            }
+        }
+    }
+
+    #[test]
+    fn test_quadratic_reciprocity() {
+        let primes = odd_primes(200);
+        for &p in &primes {
+            for &q in &primes {
+                if p == q {
+                    continue;
+                }
+                let sign = if (p - 1) / 2 * (q - 1) / 2 % 2 == 0 {
+                    1
+                } else {
+                    -1
+                };
+                assert_eq!(legendre(p, q) * legendre(q, p), sign, "p={} q={}", p, q);
+            }
+        }
+    }
+
+    #[test]
+    fn test_jacobi_is_product_of_legendre() {
+        for n in (1..300).step_by(2) {
+            for a in -20..50 {
+                let expected: i8 = factors(n).map(|p| legendre(a, p)).product();
+                assert_eq!(jacobi(a, n), expected, "({}/{})", a, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_jacobi_not_a_residue() {
+        assert_eq!(jacobi(2, 15), 1);
+        assert!(!quadratic_residues(15).contains(2));
+    }
+
+    #[test]
+    fn test_other_types() {
+        for n in (1..100_u64).step_by(2) {
+            for a in 0..100_u64 {
+                let expected = jacobi(a as i64, n as i64);
+                assert_eq!(jacobi(a, n), expected);
+                assert_eq!(jacobi(BigInt::from(a), BigInt::from(n)), expected);
+            }
+        }
+
+        // 2^127 - 1 is prime, and is 7 mod 8, so 2 is a residue.
+        let m127: BigInt = (BigInt::from(1) << 127) - 1;
+        assert_eq!(legendre(BigInt::from(2), m127.clone()), 1);
+        assert_eq!(legendre(BigInt::from(3), m127), -1);
+    }
+
+    #[test]
+    fn test_kronecker() {
+        // Agrees with Jacobi for odd positive n.
+        for n in (1..50).step_by(2) {
+            for a in -30..30 {
+                assert_eq!(kronecker(a, n), jacobi(a, n));
+            }
+        }
+
+        // (a/2) depends on a mod 8.
+        for a in -16..16_i64 {
+            let expected = match a.rem_euclid(8) {
+                1 | 7 => 1,
+                3 | 5 => -1,
+                _ => 0,
+            };
+            assert_eq!(kronecker(a, 2), expected, "({}/2)", a);
+        }
+
+        // (a/-1) is the sign of a.
+        assert_eq!(kronecker(5, -1), 1);
+        assert_eq!(kronecker(-5, -1), -1);
+
+        // -2^63 is fine, even though 2^63 doesn't fit in an i64.
+        for a in [3_i64, -3, 5, 7, i64::MAX] {
+            let expected = kronecker(BigInt::from(a), BigInt::from(i64::MIN));
+            assert_eq!(kronecker(a, i64::MIN), expected, "({}/-2^63)", a);
+        }
+        assert_eq!(kronecker(3_i64, i64::MIN), -1);
+        assert_eq!(kronecker(2_i64, i64::MIN), 0);
+
+        assert_eq!(kronecker(1, 0), 1);
+        assert_eq!(kronecker(-1, 0), 1);
+        assert_eq!(kronecker(2, 0), 0);
+        assert_eq!(kronecker(1u64, 0), 1);
+        assert_eq!(kronecker(2u64, 0), 0);
+        assert_eq!(kronecker(0u64, 0), 0);
+        assert_eq!(kronecker(u64::MAX, 0), 0);
+
+        // Multiplicative in n.
+        for a in -20..20 {
+            for m in (-12..12).filter(|&m| m != 0) {
+                for n in (-12..12).filter(|&n| n != 0) {
+                    assert_eq!(
+                        kronecker(a, m * n),
+                        kronecker(a, m) * kronecker(a, n),
+                        "a={} m={} n={}",
+                        a,
+                        m,
+                        n
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,8 @@
 use euler::factors;
 use euler::fibs;
-use euler::is_palindrome;
-use euler::partitions3;
-use itertools::Itertools;
+use euler::palindromes;
+use euler::partitions_k;
+use euler::Best;
 use primal::Sieve;
 use primal::StreamingSieve;
 
@@ -31,12 +31,12 @@ pub fn p3() -> i64 {
     factors(600851475143).last().unwrap()
 }
 
-pub fn p4() -> i64 {
-    (100_i64..1000)
-        .cartesian_product(100..1000)
-        .map(|(a, b)| a * b)
-        .filter(|n| is_palindrome(*n))
-        .max()
+pub fn p4() -> u64 {
+    // Go down through the 6 digit palindromes, and stop at the first
+    // one that splits into two 3 digit factors.
+    palindromes(6, 10)
+        .rev()
+        .find(|n| (100..1000).any(|a| n % a == 0 && (100..1000).contains(&(n / a))))
         .unwrap()
 }
 
@@ -71,12 +71,16 @@ pub fn p7() -> usize {
     StreamingSieve::nth_prime(10_001)
 }
 
-pub fn p9() -> i64 {
-    partitions3(1000)
-        .filter(|(a, b, c)| a * a + b * b == c * c)
-        .map(|(a, b, c)| a * b * c)
-        .max()
-        .unwrap()
+pub fn p9() -> u64 {
+    // c is the hypotenuse, so c < a + b, which means c < 500.
+    let mut triples = partitions_k(1000, 3).strict().max_part(499);
+    let mut best = Best::new();
+    while let Some(&[a, b, c]) = triples.next_partition() {
+        if a * a + b * b == c * c {
+            best.max(a * b * c);
+        }
+    }
+    best.into_inner().unwrap()
 }
 
 pub fn p10() -> i64 {
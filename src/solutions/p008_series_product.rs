@@ -16,7 +16,7 @@ pub fn p008() -> u64 {
 
 // Given an array slice and a window size N, returns all size-N windows
 // as slices of the original.
-fn windows<T>(slice: &'_ [T], n: usize) -> Windows<T> {
+fn windows<T>(slice: &'_ [T], n: usize) -> Windows<'_, T> {
     Windows { slice, n, i: 0 }
 }
 
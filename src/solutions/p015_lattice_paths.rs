@@ -1,25 +1,9 @@
-euler::solution!(p015, "Lattice paths", 137846528820);
-
-pub fn p015() -> usize {
-    let size = 21;
-
-    let mut num_routes = vec![0; size * size];
+use euler::binomial;
 
-    // Initialize the top and left edges - each vertex has exactly one
-    // way to get to it.
-    for x in 0..size {
-        num_routes[x] = 1;
-        num_routes[x * size] = 1;
-    }
-
-    for y in 1..size {
-        for x in 1..size {
-            // Vertex (x, y) could come from (x-1, y) or (x, y-1).
-            let pos1 = x - 1 + y * size;
-            let pos2 = x + (y - 1) * size;
-            num_routes[x + y * size] = num_routes[pos1] + num_routes[pos2];
-        }
-    }
+euler::solution!(p015, "Lattice paths", 137846528820);
 
-    num_routes[size * size - 1]
+pub fn p015() -> u128 {
+    // Every route is 40 steps, 20 of which go right, so there's one
+    // route for each way of choosing which 20.
+    binomial(40, 20)
 }
@@ -70,7 +70,7 @@ pub struct Card {
 
 impl PartialOrd for Card {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.rank.partial_cmp(&other.rank)
+        Some(self.cmp(other))
     }
 }
 
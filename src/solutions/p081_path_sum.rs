@@ -105,11 +105,11 @@ fn min_path_sum(
     //     }
     //     println!();
     // }
-    return end_nodes
+    end_nodes
         .iter()
         .map(|&(x, y)| costs[x + y * size])
         .min()
-        .unwrap();
+        .unwrap()
 }
 
 pub struct Matrix {
@@ -1,43 +1,52 @@
-euler::solution!(p092, "Square digit chains", 8_581_146);
+use euler::count_up_to;
+use euler::digits;
+use euler::DigitDp;
+use euler::OrbitCache;
+use num_traits::ToPrimitive;
 
-const N: usize = 10_000_000;
+euler::solution!(p092, "Square digit chains", 8_581_146);
 
 fn p092() -> usize {
     // Maximum square digit sum up to 10_000_000 is for 9_999_999,
     // which is 81 * 7 = 567. So we can make a lookup table from
-    // square sum of digits to loop type.
-    let types = gen_loop_types();
-    let mut count = 0;
-    for n in 2..N {
-        let sum = square_digit_sum(n);
-        if types[sum] == 89 {
-            count += 1;
-        }
-    }
-    count
+    // square sum of digits to whether it ends up at 89.
+    //
+    // The square digit sum is all that matters, so rather than going
+    // through all 10 million numbers, a digit DP can count how many
+    // numbers have each sum.
+    let reaches_89 = gen_reaches_89();
+    let count = count_up_to(&SquareDigitSum(&reaches_89), "9999999");
+    count.to_usize().unwrap()
 }
 
-fn gen_loop_types() -> [usize; 600] {
-    let mut types = [0; 600];
-    for (n, p) in types.iter_mut().enumerate() {
-        *p = loop_type(n);
+struct SquareDigitSum<'a>(&'a [bool]);
+
+impl DigitDp for SquareDigitSum<'_> {
+    type State = usize;
+
+    fn initial(&self) -> usize {
+        0
+    }
+
+    fn transition(&self, sum: &usize, digit: u32) -> Option<usize> {
+        Some(sum + (digit * digit) as usize)
     }
-    types
-}
 
-fn loop_type(mut n: usize) -> usize {
-    while n != 0 && n != 1 && n != 89 {
-        n = square_digit_sum(n);
+    fn accept(&self, sum: &usize) -> bool {
+        self.0[*sum]
     }
-    n
 }
 
-fn square_digit_sum(mut n: usize) -> usize {
-    let mut sum = 0;
-    while n > 0 {
-        let d = n % 10;
-        n /= 10;
-        sum += d * d;
+fn gen_reaches_89() -> [bool; 600] {
+    let mut cache = OrbitCache::new(square_digit_sum, 600);
+    let cycle_89 = cache.orbit(89).cycle_min;
+    let mut reaches_89 = [false; 600];
+    for (n, r) in reaches_89.iter_mut().enumerate() {
+        *r = cache.orbit(n).cycle_min == cycle_89;
     }
-    sum
+    reaches_89
+}
+
+fn square_digit_sum(n: usize) -> usize {
+    digits(n, 10).map(|d| (d * d) as usize).sum()
 }